@@ -0,0 +1,120 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+
+use directories::ProjectDirs;
+
+// numbers the partial files of writes in progress
+static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+// pages fetched by earlier searches, one file per url
+pub struct Cache {
+    dir: PathBuf,
+    // entries older than this are fetched again, but still used when the site can't be reached
+    ttl: Duration,
+    // skips reading entries while still storing fresh ones, set by --refresh
+    refresh: bool,
+}
+
+impl Cache {
+    pub fn new(dir: PathBuf, ttl: Duration, refresh: bool) -> Cache {
+        Cache { dir, ttl, refresh }
+    }
+
+    // the cache in the project cache directory, None when the platform doesn't have one
+    pub fn open(ttl: Duration, refresh: bool) -> Option<Cache> {
+        dir().map(|dir| Cache::new(dir, ttl, refresh))
+    }
+
+    // the status and body stored for url, if they're recent enough
+    pub fn get(&self, url: &str) -> Option<(u16, String)> {
+        if self.refresh {
+            return None;
+        }
+
+        let age = fs::metadata(self.path(url))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())?;
+        if age >= self.ttl {
+            return None;
+        }
+
+        self.read(url)
+    }
+
+    // the status and body stored for url however old they are
+    pub fn get_stale(&self, url: &str) -> Option<(u16, String)> {
+        if self.refresh {
+            return None;
+        }
+
+        self.read(url)
+    }
+
+    // the cache is only a shortcut, a page that can't be stored is fetched again next time
+    pub fn put(&self, url: &str, status: u16, body: &str) {
+        let _ = self.write(url, status, body);
+    }
+
+    fn read(&self, url: &str) -> Option<(u16, String)> {
+        let contents = fs::read_to_string(self.path(url)).ok()?;
+        let (stored_url, rest) = contents.split_once('\n')?;
+        // urls sharing a hash overwrite each other
+        if stored_url != url {
+            return None;
+        }
+        let (status, body) = rest.split_once('\n')?;
+
+        Some((status.parse().ok()?, String::from(body)))
+    }
+
+    fn write(&self, url: &str, status: u16, body: &str) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        // written next to the entry first so other threads never read half a page, each write
+        // gets its own file since workers can store the same page at once
+        let path = self.path(url);
+        let write = WRITES.fetch_add(1, Ordering::Relaxed);
+        let partial = path.with_extension(format!("{}-{}.tmp", std::process::id(), write));
+        fs::write(&partial, format!("{}\n{}\n{}", url, status, body))?;
+        fs::rename(&partial, &path)
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}", fnv1a(url)))
+    }
+}
+
+pub fn dir() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "magnetfinder").map(|dirs| dirs.cache_dir().join("http"))
+}
+
+// removes every cached page, returning how many there were
+pub fn clear(dir: &Path) -> io::Result<usize> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err),
+    };
+
+    let mut removed = 0;
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            fs::remove_file(entry.path())?;
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+// std's hasher isn't guaranteed to stay the same between releases, file names have to
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use crate::magnet::Magnet;
+use crate::Torrent;
+
+// merges torrents sharing an info hash into one row, keeping the position of the first one seen
+pub fn merge_duplicates(torrents: Vec<Torrent>) -> Vec<Torrent> {
+    let mut merged: Vec<Torrent> = Vec::with_capacity(torrents.len());
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for torrent in torrents {
+        let info_hash = match &torrent.info_hash {
+            Some(hash) => hash.clone(),
+            None => {
+                merged.push(torrent);
+                continue;
+            }
+        };
+
+        match seen.get(&info_hash) {
+            Some(&index) => merge(&mut merged[index], torrent),
+            None => {
+                seen.insert(info_hash, merged.len());
+                merged.push(torrent);
+            }
+        }
+    }
+
+    merged
+}
+
+fn merge(kept: &mut Torrent, duplicate: Torrent) {
+    for source in &duplicate.sources {
+        if !kept.sources.contains(source) {
+            kept.sources.push(source);
+        }
+    }
+
+    kept.seeders = kept.seeders.max(duplicate.seeders);
+    kept.leechers = kept.leechers.max(duplicate.leechers);
+
+    if let (Ok(mut magnet), Ok(other)) = (
+        kept.magnet.parse::<Magnet>(),
+        duplicate.magnet.parse::<Magnet>(),
+    ) {
+        for tracker in &other.trackers {
+            magnet.add_tracker(tracker);
+        }
+        kept.magnet = magnet.to_string();
+    }
+
+    if kept.uploaded.is_none() {
+        kept.uploaded = duplicate.uploaded;
+    }
+    if kept.uploader.is_none() {
+        kept.uploader = duplicate.uploader;
+    }
+    if kept.category.is_none() {
+        kept.category = duplicate.category;
+    }
+    if kept.url.is_none() {
+        kept.url = duplicate.url;
+    }
+}
@@ -0,0 +1,119 @@
+use std::ops::RangeInclusive;
+
+use crate::release::Release;
+use crate::Torrent;
+
+// how a site's releases number their episodes, decides the queries sent for an episode search
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EpisodeNaming {
+    // Show.S02E05.1080p.WEB-GROUP
+    Scene,
+    // [Group] Show - 05 [1080p]
+    Fansub,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EpisodeFilter {
+    pub seasons: Option<RangeInclusive<u32>>,
+    pub episodes: Option<RangeInclusive<u32>>,
+    // also keep season packs and batches containing the wanted episodes
+    pub packs: bool,
+}
+
+impl EpisodeFilter {
+    // search queries covering the wanted episodes on a site using this naming
+    pub fn queries(&self, query: &str, naming: EpisodeNaming) -> Vec<String> {
+        let query = query.trim();
+        let episode = single(&self.episodes);
+        let mut queries = Vec::new();
+
+        match naming {
+            EpisodeNaming::Scene => match (single(&self.seasons), episode, &self.seasons) {
+                (Some(season), Some(episode), _) => {
+                    queries.push(format!("{} S{:02}E{:02}", query, season, episode));
+                    if self.packs {
+                        queries.push(format!("{} S{:02}", query, season));
+                    }
+                }
+                // episode ranges are narrowed down from the whole season
+                (_, _, Some(seasons)) => {
+                    for season in seasons.clone() {
+                        queries.push(format!("{} S{:02}", query, season));
+                    }
+                }
+                (_, _, None) => queries.push(String::from(query)),
+            },
+            // fansub releases rarely carry a season number, only episodes are searched for
+            EpisodeNaming::Fansub => match episode {
+                Some(episode) => {
+                    queries.push(format!("{} {:02}", query, episode));
+                    if self.packs {
+                        queries.push(format!("{} batch", query));
+                    }
+                }
+                None => queries.push(String::from(query)),
+            },
+        }
+
+        queries
+    }
+
+    // titles without a season number (common for anime) are matched on their episodes alone
+    pub fn matches(&self, release: &Release) -> bool {
+        if let (Some(seasons), Some(first)) = (&self.seasons, release.season) {
+            let last = release.last_season.unwrap_or(first);
+            let within = seasons.contains(&first) && seasons.contains(&last);
+            // packs spanning more seasons than searched for are kept along with other packs
+            let overlaps = first <= *seasons.end() && last >= *seasons.start();
+            if !(within || self.packs && overlaps) {
+                return false;
+            }
+        }
+
+        let episodes = match &self.episodes {
+            Some(episodes) => episodes,
+            None => return true,
+        };
+
+        // season packs and batches that don't list their episodes
+        if release.episodes.is_empty() {
+            return self.packs && release.batch;
+        }
+
+        if release.episodes.iter().all(|e| episodes.contains(e)) {
+            return true;
+        }
+
+        self.packs && release.episodes.iter().any(|e| episodes.contains(e))
+    }
+
+    pub fn apply(&self, torrents: &mut Vec<Torrent>) {
+        torrents.retain(|torrent| self.matches(&torrent.release));
+    }
+}
+
+fn single(range: &Option<RangeInclusive<u32>>) -> Option<u32> {
+    range
+        .as_ref()
+        .filter(|r| r.start() == r.end())
+        .map(|r| *r.start())
+}
+
+// parses a number or an inclusive range, eg. "5" or "5-8"
+pub fn parse_range(s: &str) -> Result<RangeInclusive<u32>, String> {
+    let invalid = || format!("'{}' is not a number or a range like 1-5", s.trim());
+
+    let (start, end) = match s.split_once('-') {
+        Some((start, end)) => (start, end),
+        None => (s, s),
+    };
+
+    let start: u32 = start.trim().parse().map_err(|_| invalid())?;
+    let end: u32 = end.trim().parse().map_err(|_| invalid())?;
+
+    if start > end {
+        return Err(format!("range {} starts after it ends", s.trim()));
+    }
+
+    Ok(start..=end)
+}
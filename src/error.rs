@@ -0,0 +1,86 @@
+use std::fmt;
+
+use crate::provider;
+
+// everything that can go wrong fetching and reading a page of results
+pub enum Error {
+    // the site couldn't be reached or the connection dropped, eg. "https://nyaa.si/: Dns Failed: ..."
+    Network(String),
+    Status { url: String, code: u16 },
+    // a cloudflare/ddos-guard challenge was served instead of results
+    Blocked(String),
+    Json(serde_json::Error),
+    // the page had no result rows and didn't say there were no results either
+    LayoutChanged { selector: &'static str },
+    // result rows were found but some couldn't be read, a page fails with it when none could
+    PartialRows { skipped: usize, rows: usize },
+    NoMirrors(&'static str),
+}
+
+impl Error {
+    // the http status the site answered with, if it got that far
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Status { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Network(err) => write!(f, "{}", err),
+            Error::Status { url, code } => write!(f, "{}: status code {}", url, code),
+            Error::Blocked(url) => write!(f, "{}: mirror returned a block page", url),
+            Error::Json(err) => write!(f, "failed to decode json: {}", err),
+            Error::LayoutChanged { selector } => write!(
+                f,
+                "no rows matched '{}', the site's layout may have changed",
+                selector
+            ),
+            Error::PartialRows { skipped, rows } => {
+                write!(f, "{} of {} rows couldn't be read", skipped, rows)?;
+                if skipped == rows {
+                    write!(f, ", the site's layout may have changed")?;
+                }
+                Ok(())
+            }
+            Error::NoMirrors(name) => write!(f, "no mirrors configured for {}", name),
+        }
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ureq::Error> for Error {
+    fn from(err: ureq::Error) -> Self {
+        match err {
+            ureq::Error::Status(code, response) => {
+                let url = String::from(response.get_url());
+                // challenges are usually served as 403 or 503 rather than 200
+                if matches!(code, 403 | 429 | 503)
+                    && response
+                        .into_string()
+                        .is_ok_and(|body| provider::is_block_page(&body))
+                {
+                    return Error::Blocked(url);
+                }
+                Error::Status { url, code }
+            }
+            ureq::Error::Transport(transport) => Error::Network(transport.to_string()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
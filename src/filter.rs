@@ -0,0 +1,77 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::release::Source;
+use crate::size::Size;
+use crate::Torrent;
+
+#[derive(Clone, Default)]
+pub struct Filter {
+    pub min_size: Option<Size>,
+    pub max_size: Option<Size>,
+    pub min_seeders: Option<u32>,
+    // matched against torrent titles, case insensitive unless the pattern says otherwise
+    pub include: Option<Regex>,
+    pub exclude: Option<Regex>,
+    // torrents whose resolution couldn't be read from the title are kept
+    pub min_resolution: Option<u32>,
+    pub exclude_sources: Vec<Source>,
+    pub min_relevance: Option<u32>,
+}
+
+impl Filter {
+    pub fn matches(&self, torrent: &Torrent) -> bool {
+        if let Some(min_size) = self.min_size {
+            if torrent.size < min_size {
+                return false;
+            }
+        }
+        if let Some(max_size) = self.max_size {
+            if torrent.size > max_size {
+                return false;
+            }
+        }
+        if let Some(min_seeders) = self.min_seeders {
+            if torrent.seeders < min_seeders {
+                return false;
+            }
+        }
+        if let Some(include) = &self.include {
+            if !include.is_match(&torrent.title) {
+                return false;
+            }
+        }
+        if let Some(exclude) = &self.exclude {
+            if exclude.is_match(&torrent.title) {
+                return false;
+            }
+        }
+
+        if let (Some(min_resolution), Some(resolution)) =
+            (self.min_resolution, torrent.release.resolution)
+        {
+            if resolution < min_resolution {
+                return false;
+            }
+        }
+        if let Some(min_relevance) = self.min_relevance {
+            if torrent.relevance < min_relevance {
+                return false;
+            }
+        }
+        if let Some(source) = torrent.release.source {
+            if self.exclude_sources.contains(&source) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    pub fn apply(&self, torrents: &mut Vec<Torrent>) {
+        torrents.retain(|torrent| self.matches(torrent));
+    }
+}
+
+pub fn build_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).case_insensitive(true).build()
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use ureq::{Agent, AgentBuilder, ErrorKind, Proxy, Response};
+
+use crate::cache::Cache;
+use crate::error::Error;
+use crate::limits::{self, RateLimiter};
+
+// connection settings from [http], each site can override them in [http.<site>]
+#[derive(Clone, PartialEq, Debug)]
+pub struct HttpSettings {
+    pub connect_timeout: Duration,
+    pub read_timeout: Duration,
+    // extra attempts after a server error or dropped connection
+    pub retries: u32,
+    // wait before the first retry, doubled for every retry after it
+    pub backoff: Duration,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        HttpSettings {
+            connect_timeout: Duration::from_secs(10),
+            read_timeout: Duration::from_secs(30),
+            retries: 2,
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct HttpConfig {
+    pub global: HttpSettings,
+    pub providers: HashMap<String, HttpSettings>,
+}
+
+impl HttpConfig {
+    pub fn for_provider(&self, name: &str) -> &HttpSettings {
+        self.providers.get(name).unwrap_or(&self.global)
+    }
+}
+
+// an agent that retries failed requests
+pub struct Client {
+    agent: Agent,
+    retries: u32,
+    backoff: Duration,
+    // every attempt, retries included, waits its turn with the host
+    limiter: Option<Arc<RateLimiter>>,
+    cache: Option<Arc<Cache>>,
+}
+
+impl Client {
+    pub fn new(settings: &HttpSettings, proxy: Option<&Proxy>) -> Client {
+        let mut builder = AgentBuilder::new()
+            .timeout_connect(settings.connect_timeout)
+            .timeout_read(settings.read_timeout);
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+
+        Client {
+            agent: builder.build(),
+            retries: settings.retries,
+            backoff: settings.backoff,
+            limiter: None,
+            cache: None,
+        }
+    }
+
+    pub fn with_rate_limit(mut self, limiter: Arc<RateLimiter>) -> Client {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    pub fn with_cache(mut self, cache: Arc<Cache>) -> Client {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn cache(&self) -> Option<&Cache> {
+        self.cache.as_deref()
+    }
+
+    pub fn get(&self, url: &str) -> Result<Response, Error> {
+        let mut backoff = self.backoff;
+        let mut attempt = 0;
+
+        loop {
+            if let Some(limiter) = &self.limiter {
+                limiter.acquire(limits::host(url));
+            }
+
+            match self.agent.get(url).call() {
+                Err(err) if attempt < self.retries && is_retryable(&err) => {
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                result => return Ok(result?),
+            }
+        }
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::new(&HttpSettings::default(), None)
+    }
+}
+
+// server errors and connections dropped mid request are worth another try, timeouts aren't
+// since the site already had its chance and dns or refused connections won't fix themselves
+fn is_retryable(err: &ureq::Error) -> bool {
+    match err {
+        ureq::Error::Status(code, _) => *code >= 500,
+        ureq::Error::Transport(transport) => {
+            let timed_out = std::error::Error::source(transport)
+                .and_then(|source| source.downcast_ref::<io::Error>())
+                .is_some_and(|err| {
+                    matches!(
+                        err.kind(),
+                        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+                    )
+                });
+
+            err.kind() == ErrorKind::Io && !timed_out
+        }
+    }
+}
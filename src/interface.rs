@@ -1,25 +1,44 @@
-use std::io;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::ops::{Range, RangeInclusive};
 use std::path::PathBuf;
 use std::process;
 use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
 use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 use clap::ArgMatches;
 use comfy_table::modifiers::UTF8_ROUND_CORNERS;
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::{ContentArrangement, Table};
 
-use crate::{Media, Settings, Sort, Torrent, TorrentClient, UserParameters, Website};
+use crate::cache::Cache;
+use crate::episode::{self, EpisodeFilter};
+use crate::filter;
+use crate::output;
+use crate::profile::Profile;
+use crate::provider::{self, Provider};
+use crate::release;
+use crate::relevance;
+use crate::search::{self, Search};
+use crate::selection::{self, Command};
+use crate::sort;
+use crate::{Media, OutputFormat, Settings, SortKey, Torrent, TorrentClient, UserParameters};
+
+fn parse_providers(s: &str) -> Result<Vec<Arc<dyn Provider>>, String> {
+    let name = s.trim().to_lowercase();
+    if name == "all" {
+        return Ok(provider::registry());
+    }
 
-impl Website {
-    fn new(s: &str) -> Result<Vec<Website>, &'static str> {
-        match s.trim().to_lowercase().as_str() {
-            "nyaa" => Ok(vec![Website::Nyaa]),
-            "piratebay" => Ok(vec![Website::Piratebay]),
-            "yts" => Ok(vec![Website::YTS]),
-            "all" => Ok(vec![Website::Nyaa, Website::Piratebay, Website::YTS]),
-            _ => Err("Unknown website, supported sites: nyaa, piratebay"),
-        }
+    match provider::find(&name) {
+        Some(provider) => Ok(vec![provider]),
+        None => Err(format!(
+            "Unknown website, supported sites: {}",
+            provider::names().join(", ")
+        )),
     }
 }
 
@@ -43,15 +62,6 @@ impl Media {
     }
 }
 
-impl Sort {
-    fn new(s: &str) -> Sort {
-        match s.to_lowercase().as_str() {
-            "size" => Sort::Size,
-            _ => Sort::Seeds,
-        }
-    }
-}
-
 impl TorrentClient {
     fn new(s: &str) -> TorrentClient {
         match s.to_lowercase().as_str() {
@@ -63,6 +73,9 @@ impl TorrentClient {
     }
 }
 
+// scores are all equal without a quality profile, leaving results sorted by seeders
+const DEFAULT_SORT: &str = "score,seeds";
+
 impl UserParameters {
     pub fn get_params(args: ArgMatches) -> UserParameters {
         if !args_present(&args) {
@@ -85,35 +98,60 @@ impl UserParameters {
             }
         };
 
+        let mut providers = UserParameters::get_providers();
+        let media = UserParameters::get_media();
+
+        // when searching several sites at once skip the ones that don't carry this media
+        if providers.len() > 1 {
+            providers.retain(|provider| provider.capabilities().media.contains(&media));
+        }
+
+        let search_query = UserParameters::get_search_query();
+
+        let episodes = match media {
+            Media::Anime | Media::TVShow => UserParameters::get_episodes(),
+            Media::Movie => None,
+        };
+        if episodes.is_some() {
+            providers.retain(|provider| provider.capabilities().episode_naming.is_some());
+        }
+
         UserParameters {
-            websites: UserParameters::get_websites(),
-            directory: UserParameters::get_media().path(&settings),
-            search_query: UserParameters::get_search_query(),
-            search_depth: 1,
-            sort_preference: Sort::new("seeds"),
+            providers,
+            directory: media.path(&settings),
+            search_query,
+            search_depth: Some(1),
+            timeout: timeout(settings.timeout),
+            episodes,
+            sort_preference: sort::parse_sort_keys(DEFAULT_SORT).unwrap(),
             num_torrents_shown: usize::MAX,
-            proxy: Arc::new(settings.default_proxy),
+            proxy: parse_proxy(&settings.default_proxy),
+            http: settings.http,
+            limits: settings.limits,
+            cache: open_cache(settings.cache_ttl, false),
+            mirrors: settings.mirrors,
+            trackers: settings.trackers,
+            filter: settings.filter,
+            profile: settings.profiles.for_media(media).clone(),
             autodownload: settings.autodownload,
             torrent_client: TorrentClient::new(&settings.torrent_client),
             no_interactive: false,
+            tui: settings.tui,
+            verbose: false,
+            output: OutputFormat::Text,
         }
     }
 
     // parses provided cmd arguments bypassing user interface prompt
     fn fetch(args: ArgMatches) -> UserParameters {
-        let mut websites: Vec<Website> = Vec::new();
-        if args.is_present("nyaa") {
-            websites.push(Website::Nyaa);
-        }
-        if args.is_present("piratebay") {
-            websites.push(Website::Piratebay);
-        }
-        if args.is_present("yts") {
-            websites.push(Website::YTS);
-        }
-        if args.is_present("all") {
-            websites = Website::new("all").unwrap();
-        }
+        let mut providers: Vec<Arc<dyn Provider>> = if args.is_present("all") {
+            provider::registry()
+        } else {
+            provider::registry()
+                .into_iter()
+                .filter(|provider| args.is_present(provider.name()))
+                .collect()
+        };
 
         let config_settings = match Settings::fetch() {
             Ok(s) => s,
@@ -126,9 +164,14 @@ impl UserParameters {
             }
         };
 
-        if websites.is_empty() {
+        if providers.is_empty() {
+            let flags: Vec<String> = provider::registry()
+                .iter()
+                .map(|provider| format!("-{} for {}", provider.short_flag(), provider.name()))
+                .collect();
             eprintln!(
-                "Must select website to scrape from, -n for nyaa, -p for piratebay, -a for all"
+                "Must select website to scrape from, {}, -a for all",
+                flags.join(", ")
             );
             process::exit(1);
         }
@@ -144,62 +187,144 @@ impl UserParameters {
             None => config_settings.default_directory,
         };
 
-        let search_depth: u32 = match args.value_of("depth") {
-            Some(n) => n.trim().parse().unwrap_or(1),
-            None => 1,
+        let seasons = parsed_arg(&args, "season", episode::parse_range);
+        let episodes = parsed_arg(&args, "episode", episode::parse_range);
+        let episodes = if seasons.is_some() || episodes.is_some() {
+            Some(EpisodeFilter {
+                seasons,
+                episodes,
+                packs: args.is_present("packs"),
+            })
+        } else {
+            None
         };
 
+        if episodes.is_some() {
+            providers.retain(|provider| provider.capabilities().episode_naming.is_some());
+            if providers.is_empty() {
+                eprintln!("None of the selected websites carry episodes to search for");
+                process::exit(1);
+            }
+        }
+
+        let search_depth = parsed_arg(&args, "depth", search::parse_depth).unwrap_or(Some(1));
+
+        let timeout =
+            timeout(parsed_arg(&args, "timeout", str::parse).unwrap_or(config_settings.timeout));
+
         let search_query = Arc::new(String::from(args.value_of("query").unwrap_or_else(|| {
             eprintln!("Must provide a valid search query (-q/--query \"search term\")");
             process::exit(1);
         })));
 
-        let sort_preference = Sort::new(args.value_of("sort").unwrap_or("seeds"));
+        let sort_preference = parsed_arg(&args, "sort", sort::parse_sort_keys)
+            .unwrap_or_else(|| sort::parse_sort_keys(DEFAULT_SORT).unwrap());
+
+        // sites carrying a single type of media imply it, eg. -n searches anime
+        let media = parsed_arg(&args, "media", Media::new).or_else(|| single_media(&providers));
+        let profile = match media {
+            Some(media) => config_settings.profiles.for_media(media).clone(),
+            None => Profile::default(),
+        };
 
         let num_torrents_shown: usize = match args.value_of("num_torrents_shown") {
             Some(n) => n.trim().parse().unwrap_or(usize::MAX),
             None => usize::MAX,
         };
 
-        let proxy = match args.value_of("proxy") {
-            Some(p) => Arc::new(String::from(p)),
-            None => Arc::new(config_settings.default_proxy),
+        let proxy = parse_proxy(
+            args.value_of("proxy")
+                .unwrap_or(&config_settings.default_proxy),
+        );
+
+        let cache = if args.is_present("no_cache") {
+            None
+        } else {
+            open_cache(config_settings.cache_ttl, args.is_present("refresh"))
         };
 
         let torrent_client = TorrentClient::new(&config_settings.torrent_client);
 
+        // structured output is meant for scripts, so it skips the interactive table
+        let output = match parsed_arg(&args, "format", str::parse) {
+            Some(template) => OutputFormat::Template(template),
+            None => parsed_arg(&args, "output", str::parse).unwrap_or(OutputFormat::Text),
+        };
+
+        let mut filter = config_settings.filter;
+        if let Some(size) = parsed_arg(&args, "min_size", str::parse) {
+            filter.min_size = Some(size);
+        }
+        if let Some(size) = parsed_arg(&args, "max_size", str::parse) {
+            filter.max_size = Some(size);
+        }
+        if let Some(seeders) = parsed_arg(&args, "min_seeders", str::parse) {
+            filter.min_seeders = Some(seeders);
+        }
+        if let Some(include) = parsed_arg(&args, "include", filter::build_regex) {
+            filter.include = Some(include);
+        }
+        if let Some(exclude) = parsed_arg(&args, "exclude", filter::build_regex) {
+            filter.exclude = Some(exclude);
+        }
+        if let Some(resolution) = parsed_arg(&args, "min_resolution", release::parse_resolution_arg)
+        {
+            filter.min_resolution = Some(resolution);
+        }
+        if let Some(sources) = parsed_arg(&args, "exclude_source", release::parse_sources) {
+            filter.exclude_sources = sources;
+        }
+        if let Some(relevance) = parsed_arg(&args, "min_relevance", relevance::parse_relevance) {
+            filter.min_relevance = Some(relevance);
+        }
+
         UserParameters {
-            websites,
+            providers,
             directory,
             search_query,
             search_depth,
+            timeout,
+            episodes,
             sort_preference,
             num_torrents_shown,
             proxy,
+            http: config_settings.http,
+            limits: config_settings.limits,
+            cache,
+            mirrors: config_settings.mirrors,
+            trackers: config_settings.trackers,
+            filter,
+            profile,
             autodownload: args.is_present("download"),
             torrent_client,
-            no_interactive: args.is_present("no-interactive"),
+            no_interactive: args.is_present("no-interactive") || output != OutputFormat::Text,
+            tui: args.is_present("tui") || config_settings.tui,
+            verbose: args.is_present("verbose"),
+            output,
         }
     }
 
-    fn get_websites() -> Vec<Website> {
+    fn get_providers() -> Vec<Arc<dyn Provider>> {
         loop {
             let mut input = String::new();
-            println!("Website(s) to search from? (nyaa, piratebay, yts, all)");
+            println!(
+                "Website(s) to search from? ({}, all)",
+                provider::names().join(", ")
+            );
 
             io::stdin()
                 .read_line(&mut input)
                 .expect("io error: failed to read website input");
 
-            let websites = match Website::new(&input) {
-                Ok(website) => website,
+            let providers = match parse_providers(&input) {
+                Ok(providers) => providers,
                 Err(err) => {
                     println!("{}", err);
                     continue;
                 }
             };
 
-            return websites;
+            return providers;
         }
     }
 
@@ -224,6 +349,33 @@ impl UserParameters {
         }
     }
 
+    // empty answers search for every episode
+    fn get_episodes() -> Option<EpisodeFilter> {
+        let seasons = prompt_range("Season? (number or range like 1-3, leave empty for any)");
+        let episodes = prompt_range("Episode? (number or range like 1-12, leave empty for any)");
+
+        if seasons.is_none() && episodes.is_none() {
+            return None;
+        }
+
+        let packs = episodes.is_some() && {
+            let mut input = String::new();
+            println!("Include season packs and batches? (y/n)");
+
+            io::stdin()
+                .read_line(&mut input)
+                .expect("io error: failed to read season pack input");
+
+            input.trim().eq_ignore_ascii_case("y")
+        };
+
+        Some(EpisodeFilter {
+            seasons,
+            episodes,
+            packs,
+        })
+    }
+
     fn get_search_query() -> Arc<String> {
         let mut input = String::new();
         println!("Search query: ");
@@ -236,107 +388,338 @@ impl UserParameters {
     }
 }
 
-pub fn display_torrent_table(torrents: &[Torrent]) -> Vec<&String> {
-    let mut torrents_shown: usize = if torrents.len() < 20 {
-        torrents.len()
-    } else {
-        20
-    };
-    loop {
-        let mut table = Table::new();
+const PAGE_SIZE: usize = 20;
+
+// how often the table checks for newly arrived results while waiting for input
+const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+// pages through the results until torrents are selected, quitting selects nothing. the table
+// is redrawn as results arrive, process turns the raw results into the rows to show
+pub fn display_torrent_table(
+    search: &mut Search,
+    verbose: bool,
+    process: impl Fn(Vec<Torrent>) -> Vec<Torrent>,
+) -> Vec<String> {
+    let input = read_lines();
+
+    let mut torrents = process(search.torrents().to_vec());
+    // indices into torrents in the order shown, after sorting and filtering at the prompt
+    let mut rows: Vec<usize> = (0..torrents.len()).collect();
+    let mut sort_keys: Vec<SortKey> = Vec::new();
+    let mut filter = String::new();
+    let mut page = 0;
+    let mut redraw = true;
+    let mut summary_shown = false;
+    // the searching line shown before any results, rewritten in place as sites answer
+    let mut loading = String::new();
 
-        table
-            .load_preset(UTF8_FULL)
-            .apply_modifier(UTF8_ROUND_CORNERS)
-            .set_content_arrangement(ContentArrangement::Dynamic)
-            .set_header(vec!["#", "Name", "Size", "Seeds"]);
+    loop {
+        if redraw {
+            if !torrents.is_empty() || search.is_finished() {
+                clear_loading(&mut loading);
+            }
 
-        let table = update_torrent_table(&mut table, &torrents[0..torrents_shown]);
-        println!("{}", table);
+            if search.is_finished() && torrents.is_empty() {
+                for line in search.summary(verbose) {
+                    eprintln!("{}", line);
+                }
+                eprintln!("No torrents found matching search query");
+                return Vec::new();
+            }
 
-        if let Some(mag) = prompt_torrent_selection(torrents) {
-            return mag;
+            if torrents.is_empty() {
+                let status = format!("Searching {}...", search.loading().join(", "));
+                if status != loading {
+                    print!("\r\x1b[2K{}", status);
+                    let _ = io::stdout().flush();
+                    loading = status;
+                }
+            } else {
+                // arriving results can shrink the table, eg. when duplicates are merged
+                page = page.min(rows.len().saturating_sub(1) / PAGE_SIZE);
+                print_torrent_table(&torrents, &rows, page);
+                print_search_status(search, &torrents, &rows, &filter);
+                // shown once, below the first table drawn after the last site answered
+                if search.is_finished() && !summary_shown {
+                    for line in search.summary(verbose) {
+                        println!("{}", line);
+                    }
+                    summary_shown = true;
+                }
+                print_prompt();
+            }
+            redraw = false;
         }
 
-        torrents_shown = if torrents.len() < torrents_shown + 20 {
-            torrents.len()
+        let line = if search.is_finished() {
+            input.recv().ok()
         } else {
-            torrents_shown + 20
+            match input.try_recv() {
+                Ok(line) => Some(line),
+                Err(TryRecvError::Empty) => {
+                    if search.poll(REFRESH_INTERVAL) {
+                        torrents = process(search.torrents().to_vec());
+                        rows = filtered_rows(&torrents, &filter, &sort_keys);
+                        redraw = true;
+                    }
+                    continue;
+                }
+                Err(TryRecvError::Disconnected) => None,
+            }
+        };
+
+        // stdin was closed
+        let line = match line {
+            Some(line) => line,
+            None => return Vec::new(),
         };
+
+        match selection::parse_command(&line, rows.len()) {
+            Ok(Command::Next) if (page + 1) * PAGE_SIZE < rows.len() => {
+                page += 1;
+                redraw = true;
+            }
+            Ok(Command::Next) => println!("No more torrents to show"),
+            Ok(Command::Previous) if page > 0 => {
+                page -= 1;
+                redraw = true;
+            }
+            Ok(Command::Previous) => println!("Already on the first page"),
+            Ok(Command::Quit) => return Vec::new(),
+            Ok(Command::Sort(keys)) => {
+                sort_keys = keys;
+                rows = filtered_rows(&torrents, &filter, &sort_keys);
+                page = 0;
+                redraw = true;
+            }
+            Ok(Command::Filter(f)) => {
+                filter = f;
+                rows = filtered_rows(&torrents, &filter, &sort_keys);
+                page = 0;
+                redraw = true;
+            }
+            Ok(Command::Inspect(n)) => {
+                let torrent = &torrents[rows[n]];
+                println!();
+                for line in output::details(torrent) {
+                    println!("{}", line);
+                }
+                println!("magnet: {}", torrent.magnet);
+                println!();
+            }
+            Ok(Command::Select(selection)) => {
+                return selection
+                    .into_iter()
+                    .map(|n| torrents[rows[n]].magnet.clone())
+                    .collect();
+            }
+            Err(err) => println!("{}", err),
+        }
     }
 }
 
-fn update_torrent_table<'a>(table: &'a mut Table, torrents: &[Torrent]) -> &'a Table {
-    for (n, t) in torrents.iter().enumerate() {
-        table.add_row(vec![&(n + 1).to_string(), &t.title, &t.size, &t.seeders]);
+// erases the searching line so the table or summary starts on a clean line
+fn clear_loading(loading: &mut String) {
+    if !loading.is_empty() {
+        print!("\r\x1b[2K");
+        let _ = io::stdout().flush();
+        loading.clear();
     }
+}
+
+// stdin is read on its own thread so the table can keep updating while waiting for input
+fn read_lines() -> Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || loop {
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    rx
+}
+
+fn print_torrent_table(torrents: &[Torrent], rows: &[usize], page: usize) {
+    let start = page * PAGE_SIZE;
+    let end = rows.len().min(start + PAGE_SIZE);
+
+    let mut table = Table::new();
 
     table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            "#", "Name", "Quality", "Size", "Seeds", "Leech", "Date", "Site",
+        ]);
+
+    let table = update_torrent_table(&mut table, torrents, rows, start..end);
+    println!("{}", table);
 }
 
-pub fn prompt_torrent_selection(torrents: &[Torrent]) -> Option<Vec<&String>> {
-    loop {
-        println!("Type 'n' to display 20 more torrents, or select torrent(s) by #:");
+fn print_search_status(search: &Search, torrents: &[Torrent], rows: &[usize], filter: &str) {
+    if !filter.is_empty() {
+        println!(
+            "{} of {} torrents match '{}', 'f' alone clears the filter",
+            rows.len(),
+            torrents.len(),
+            filter
+        );
+    }
 
-        let mut selections = String::new();
+    let loading = search.loading();
+    if !loading.is_empty() {
+        println!(
+            "Still searching {}, results are added as they arrive",
+            loading.join(", ")
+        );
+    }
+}
 
-        io::stdin()
-            .read_line(&mut selections)
-            .expect("io error: couldn't read torrent selection input");
+fn print_prompt() {
+    println!("Select torrent(s) by # (eg. 1 3-5,8, all or ^2), or type a command:");
+    println!("  n/p next/previous page, s <keys> sort, f <words> filter, i <#> inspect, q quit");
+}
 
-        let selections: Vec<&str> = selections.trim().split(' ').collect();
-        if selections.is_empty() {
-            println!(
-                "Please input one or multiple numbers seperated by a space to select torrent(s)"
-            );
-            continue;
-        }
+// titles must contain every word of the filter, sorting keeps the search order for ties
+fn filtered_rows(torrents: &[Torrent], filter: &str, sort_keys: &[SortKey]) -> Vec<usize> {
+    let terms: Vec<String> = filter.split_whitespace().map(str::to_lowercase).collect();
+
+    let mut rows: Vec<usize> = (0..torrents.len())
+        .filter(|&i| {
+            let title = torrents[i].title.to_lowercase();
+            terms.iter().all(|term| title.contains(term.as_str()))
+        })
+        .collect();
+    rows.sort_by(|&a, &b| sort::compare_torrents(&torrents[a], &torrents[b], sort_keys));
+
+    rows
+}
+
+fn update_torrent_table<'a>(
+    table: &'a mut Table,
+    torrents: &[Torrent],
+    rows: &[usize],
+    page: Range<usize>,
+) -> &'a Table {
+    for n in page {
+        let t = &torrents[rows[n]];
+        let uploaded = match t.uploaded {
+            Some(date) => date.format("%Y-%m-%d").to_string(),
+            None => String::new(),
+        };
 
-        if selections[0].to_lowercase() == "q" {
+        table.add_row(vec![
+            (n + 1).to_string(),
+            t.title.clone(),
+            t.release.summary(),
+            t.size.to_string(),
+            t.seeders.to_string(),
+            t.leechers.to_string(),
+            uploaded,
+            t.sources.join(", "),
+        ]);
+    }
+
+    table
+}
+
+// exits when the user gave a value that can't be parsed rather than silently ignoring it
+fn parsed_arg<T, E: Display>(
+    args: &ArgMatches,
+    name: &str,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Option<T> {
+    let value = args.value_of(name)?;
+
+    match parse(value.trim()) {
+        Ok(v) => Some(v),
+        Err(err) => {
+            eprintln!(
+                "Invalid value '{}' for --{}: {}",
+                value,
+                name.replace('_', "-"),
+                err
+            );
             process::exit(1);
         }
+    }
+}
 
-        if selections[0].to_lowercase() == "n" {
+fn prompt_range(prompt: &str) -> Option<RangeInclusive<u32>> {
+    loop {
+        let mut input = String::new();
+        println!("{}", prompt);
+
+        io::stdin()
+            .read_line(&mut input)
+            .expect("io error: failed to read episode input");
+
+        if input.trim().is_empty() {
             return None;
         }
 
-        let magnets = match collect_magnet_links(torrents, &selections) {
-            Ok(m) => m,
-            Err(s) => {
-                println!("{}", s);
-                continue;
-            }
-        };
-
-        return Some(magnets);
+        match episode::parse_range(&input) {
+            Ok(range) => return Some(range),
+            Err(err) => println!("{}", err),
+        }
     }
 }
 
-fn collect_magnet_links<'a>(
-    torrents: &'a [Torrent],
-    selections: &[&str],
-) -> Result<Vec<&'a String>, &'static str> {
-    let mut magnets = Vec::new();
-    for num_str in selections {
-        let num: usize = match num_str.parse() {
-            Err(_) => {
-                return Err("Only input numbers indicated on the left-most column");
-            }
-            Ok(num) => num,
-        };
+// exits rather than letting the search go out without the proxy
+fn parse_proxy(proxy: &str) -> Option<ureq::Proxy> {
+    if proxy.trim().is_empty() {
+        return None;
+    }
 
-        if num > torrents.len() || num == 0 {
-            return Err("Input out of range");
+    match ureq::Proxy::new(proxy.trim()) {
+        Ok(proxy) => Some(proxy),
+        Err(err) => {
+            eprintln!("Invalid proxy '{}': {}", proxy, err);
+            process::exit(1);
         }
+    }
+}
 
-        magnets.push(&torrents[num - 1].magnet);
+fn timeout(seconds: u64) -> Option<Duration> {
+    if seconds == 0 {
+        None
+    } else {
+        Some(Duration::from_secs(seconds))
     }
-    Ok(magnets)
+}
+
+fn open_cache(ttl: u64, refresh: bool) -> Option<Arc<Cache>> {
+    if ttl == 0 {
+        return None;
+    }
+
+    Cache::open(Duration::from_secs(ttl), refresh).map(Arc::new)
+}
+
+fn single_media(providers: &[Arc<dyn Provider>]) -> Option<Media> {
+    let media = providers.first()?.capabilities().media;
+    if media.len() != 1 {
+        return None;
+    }
+
+    providers
+        .iter()
+        .all(|provider| provider.capabilities().media == media)
+        .then(|| media[0])
 }
 
 fn args_present(args: &ArgMatches) -> bool {
-    args.is_present("nyaa")
-        || args.is_present("piratebay")
+    provider::registry()
+        .iter()
+        .any(|provider| args.is_present(provider.name()))
         || args.is_present("all")
         || args.is_present("download")
         || args.is_present("directory")
@@ -1,99 +1,178 @@
+pub mod cache;
+pub mod dedup;
+pub mod episode;
+pub mod error;
+pub mod filter;
+pub mod http;
 pub mod interface;
+pub mod limits;
+pub mod magnet;
 pub mod nyaa;
+pub mod output;
 pub mod piratebay;
+pub mod profile;
+pub mod provider;
+pub mod release;
+pub mod relevance;
+pub mod search;
+pub mod selection;
 pub mod settings;
+pub mod size;
+pub mod sort;
+pub mod trackers;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod types;
 pub mod yts;
 
-use std::cmp::Reverse;
+use std::io;
 use std::process;
-use std::sync::{mpsc, Arc};
 
 use clap::ArgMatches;
-use ureq::{Agent, AgentBuilder};
 
-use types::{Media, Settings, Sort, Torrent, TorrentClient, UserParameters, Website};
+use search::Search;
+use types::{
+    Media, Order, OutputFormat, Settings, Sort, SortKey, Torrent, TorrentClient, UserParameters,
+};
 
 pub fn run(args: ArgMatches) {
+    if let Some(cache_args) = args.subcommand_matches("cache") {
+        if cache_args.subcommand_name() == Some("clear") {
+            clear_cache();
+        }
+        return;
+    }
+
     let user_parameters = UserParameters::get_params(args);
 
-    let client = Arc::new(match build_http_client(&user_parameters.proxy) {
-        Ok(client) => client,
-        Err(_) => Agent::new(),
-    });
+    let search = Search::start(&user_parameters);
 
-    let (tx, rx) = mpsc::channel();
-    for website in user_parameters.websites {
-        match website {
-            Website::Nyaa => nyaa::query(
-                &client,
-                tx.clone(),
-                &user_parameters.search_query,
-                user_parameters.search_depth,
-            ),
-            Website::Piratebay => piratebay::query(
-                &client,
-                tx.clone(),
-                &user_parameters.search_query,
-                user_parameters.search_depth,
-            ),
-            Website::YTS => yts::query(
-                &client,
-                tx.clone(),
-                &user_parameters.search_query,
-                user_parameters.search_depth,
-            ),
-        };
-    }
-    drop(tx);
-
-    let mut torrents: Vec<Torrent> = Vec::new();
-    for received_torrents in rx {
-        torrents.extend(received_torrents);
-    }
-
-    match user_parameters.sort_preference {
-        Sort::Size => torrents.sort_by_key(|t| Reverse(t.get_size_as_i64())),
-        Sort::Seeds => torrents.sort_by_key(|t| Reverse((t.seeders).parse().unwrap_or(0))),
+    if !user_parameters.no_interactive {
+        if user_parameters.tui {
+            browse_torrents(&user_parameters, search);
+        } else {
+            show_torrent_table(&user_parameters, search);
+        }
+        return;
     }
 
+    let torrents = finish_search(&user_parameters, search);
     if torrents.is_empty() {
+        // scripts still get a valid, empty document
         eprintln!("No torrents found matching search query");
-        return;
     }
 
+    if let Err(err) =
+        output::write_torrents(&mut io::stdout().lock(), &torrents, &user_parameters.output)
+    {
+        // output piped into eg. head stops reading early
+        if err.kind() != io::ErrorKind::BrokenPipe {
+            eprintln!("Error writing results: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+// the table is shown straight away and updated as results arrive
+fn show_torrent_table(user_parameters: &UserParameters, mut search: Search) {
+    let magnets =
+        interface::display_torrent_table(&mut search, user_parameters.verbose, |torrents| {
+            process_torrents(user_parameters, torrents)
+        });
+    handle_magnets(user_parameters, &magnets);
+}
+
+// waits for every site to answer or time out
+fn finish_search(user_parameters: &UserParameters, mut search: Search) -> Vec<Torrent> {
+    search.wait();
+    // kept off stdout, which scripts read results from
+    for line in search.summary(user_parameters.verbose) {
+        eprintln!("{}", line);
+    }
+
+    process_torrents(user_parameters, search.into_torrents())
+}
+
+// turns raw search results into the rows shown, this runs again whenever more results arrive
+fn process_torrents(user_parameters: &UserParameters, mut torrents: Vec<Torrent>) -> Vec<Torrent> {
+    for torrent in &mut torrents {
+        if let Some(source) = torrent.sources.first() {
+            torrent.magnet = user_parameters
+                .trackers
+                .for_provider(source)
+                .apply(&torrent.magnet);
+        }
+    }
+
+    let mut torrents = dedup::merge_duplicates(torrents);
+    for torrent in &mut torrents {
+        torrent.relevance = relevance::relevance(&user_parameters.search_query, &torrent.title);
+    }
+    user_parameters.filter.apply(&mut torrents);
+    user_parameters.profile.apply(&mut torrents);
+    if let Some(episodes) = &user_parameters.episodes {
+        episodes.apply(&mut torrents);
+    }
+
+    sort::sort_torrents(&mut torrents, &user_parameters.sort_preference);
+
     if torrents.len() > user_parameters.num_torrents_shown {
         torrents.truncate(user_parameters.num_torrents_shown);
     }
 
-    if !user_parameters.no_interactive {
-        let magnets = interface::display_torrent_table(&torrents);
-
-        if user_parameters.autodownload {
-            for m in magnets {
-                download_torrent(
-                    &user_parameters.torrent_client,
-                    user_parameters.directory.to_str().unwrap(),
-                    m,
-                );
-            }
-        } else {
-            for m in magnets {
-                println!("{}", m);
-            }
+    torrents
+}
+
+fn handle_magnets(user_parameters: &UserParameters, magnets: &[String]) {
+    if user_parameters.autodownload {
+        for m in magnets {
+            download_torrent(
+                &user_parameters.torrent_client,
+                user_parameters.directory.to_str().unwrap(),
+                m,
+            );
         }
     } else {
-        for torrent in &torrents {
-            println!("{}\t{}", torrent.title, torrent.magnet);
+        for m in magnets {
+            println!("{}", m);
         }
     }
 }
+#[cfg(feature = "tui")]
+fn browse_torrents(user_parameters: &UserParameters, search: Search) {
+    let torrents = finish_search(user_parameters, search);
+    if torrents.is_empty() {
+        eprintln!("No torrents found matching search query");
+        return;
+    }
+
+    let magnets = tui::browse(&torrents).unwrap_or_else(|err| {
+        eprintln!("Error running the result browser: {}", err);
+        process::exit(1);
+    });
+    let magnets: Vec<String> = magnets.into_iter().cloned().collect();
+    handle_magnets(user_parameters, &magnets);
+}
 
-fn build_http_client(proxy: &str) -> Result<Agent, ureq::Error> {
-    if proxy.is_empty() {
-        Ok(Agent::new())
-    } else {
-        Ok(AgentBuilder::new().proxy(ureq::Proxy::new(proxy)?).build())
+#[cfg(not(feature = "tui"))]
+fn browse_torrents(user_parameters: &UserParameters, search: Search) {
+    eprintln!("magnetfinder was built without the tui feature, showing the result table instead");
+    show_torrent_table(user_parameters, search)
+}
+
+fn clear_cache() {
+    let dir = cache::dir().unwrap_or_else(|| {
+        eprintln!("Error finding project cache directory");
+        process::exit(1);
+    });
+
+    match cache::clear(&dir) {
+        Ok(1) => println!("Removed 1 cached page"),
+        Ok(removed) => println!("Removed {} cached pages", removed),
+        Err(err) => {
+            eprintln!("Error clearing cache in {}: {}", dir.display(), err);
+            process::exit(1);
+        }
     }
 }
 
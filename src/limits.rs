@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// how hard a search may hit the sites, set in [limits]
+#[derive(Clone, PartialEq, Debug)]
+pub struct Limits {
+    // pages being fetched at once across every site
+    pub concurrency: usize,
+    // sustained requests per second to a single host, 0 turns the limit off
+    pub requests_per_second: f64,
+    // requests a host may get back to back before the rate applies
+    pub burst: u32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            concurrency: 8,
+            requests_per_second: 2.0,
+            burst: 4,
+        }
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+// a fixed number of threads running jobs in the order they were queued, clones queue
+// onto the same threads
+#[derive(Clone)]
+pub struct Pool {
+    tx: Sender<Job>,
+}
+
+impl Pool {
+    pub fn new(workers: usize) -> Pool {
+        let (tx, rx) = mpsc::channel::<Job>();
+        let rx = Arc::new(Mutex::new(rx));
+
+        for _ in 0..workers.max(1) {
+            let rx = Arc::clone(&rx);
+            // workers run until every clone of the pool is dropped and every queued job is done
+            thread::spawn(move || loop {
+                let job = match rx.lock().unwrap().recv() {
+                    Ok(job) => job,
+                    Err(_) => break,
+                };
+                job();
+            });
+        }
+
+        Pool { tx }
+    }
+
+    pub fn execute(&self, job: impl FnOnce() + Send + 'static) {
+        // workers only stop once the pool is gone, so there is always one to send to
+        let _ = self.tx.send(Box::new(job));
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+// a token bucket per host, shared by every site's client
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: &Limits) -> RateLimiter {
+        RateLimiter {
+            rate: limits.requests_per_second,
+            burst: limits.burst.max(1) as f64,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // blocks until a request to host is allowed
+    pub fn acquire(&self, host: &str) {
+        if self.rate <= 0.0 {
+            return;
+        }
+
+        let wait = {
+            let mut buckets = self.buckets.lock().unwrap();
+            let now = Instant::now();
+            let bucket = buckets.entry(String::from(host)).or_insert(Bucket {
+                tokens: self.burst,
+                updated: now,
+            });
+
+            let refilled = now.duration_since(bucket.updated).as_secs_f64() * self.rate;
+            bucket.tokens = (bucket.tokens + refilled).min(self.burst);
+            bucket.updated = now;
+
+            // taking the token up front reserves a turn, waiters queue up behind each other
+            bucket.tokens -= 1.0;
+            if bucket.tokens >= 0.0 {
+                Duration::from_secs(0)
+            } else {
+                Duration::from_secs_f64(-bucket.tokens / self.rate)
+            }
+        };
+
+        thread::sleep(wait);
+    }
+}
+
+// eg. "nyaa.si" for "https://nyaa.si/?q=x", ports are kept since they're separate servers
+pub fn host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let authority = &rest[..end];
+
+    // credentials before the host
+    authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host)
+}
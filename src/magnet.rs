@@ -0,0 +1,208 @@
+use std::fmt;
+use std::str::FromStr;
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+// everything but the unreserved characters of RFC 3986 gets percent-encoded
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Magnet {
+    // v1 info hash as 40 lowercase hex characters, base32 hashes are converted
+    pub info_hash: Option<String>,
+    // v2 info hash as a lowercase hex sha2-256 multihash (starts with "1220")
+    pub info_hash_v2: Option<String>,
+    pub name: Option<String>,
+    pub trackers: Vec<String>,
+    pub length: Option<u64>,
+    pub web_seeds: Vec<String>,
+    // parameters without special handling, kept so serializing doesn't lose them
+    pub extra: Vec<(String, String)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MagnetError {
+    NotAMagnet,
+    MissingInfoHash,
+    InvalidInfoHash(String),
+    InvalidLength(String),
+}
+
+impl Magnet {
+    pub fn new(info_hash: &str) -> Result<Magnet, MagnetError> {
+        Ok(Magnet {
+            info_hash: Some(normalize_btih(info_hash)?),
+            ..Default::default()
+        })
+    }
+
+    pub fn add_tracker(&mut self, tracker: &str) {
+        if !self.trackers.iter().any(|t| t == tracker) {
+            self.trackers.push(String::from(tracker));
+        }
+    }
+
+    // hash used to recognise the same torrent across sites, v1 is preferred since
+    // hybrid torrents carry both and most sites only list the v1 hash
+    pub fn key(&self) -> Option<&str> {
+        self.info_hash.as_deref().or(self.info_hash_v2.as_deref())
+    }
+}
+
+impl FromStr for Magnet {
+    type Err = MagnetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // uri schemes are case insensitive, eg. "MAGNET:?xt=..."
+        let query = match s.get(..8) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("magnet:?") => &s[8..],
+            _ => return Err(MagnetError::NotAMagnet),
+        };
+
+        let mut magnet = Magnet::default();
+
+        for param in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            // numbered keys like "xt.1" or "tr.2" are allowed by the spec
+            let base_key = key.split('.').next().unwrap_or(key);
+
+            match base_key {
+                "xt" => {
+                    let value = decode(value);
+                    if let Some(hash) = value.strip_prefix("urn:btih:") {
+                        magnet.info_hash = Some(normalize_btih(hash)?);
+                    } else if let Some(hash) = value.strip_prefix("urn:btmh:") {
+                        magnet.info_hash_v2 = Some(normalize_btmh(hash)?);
+                    } else {
+                        magnet.extra.push((String::from(key), value));
+                    }
+                }
+                // some sites encode spaces in the display name as '+'
+                "dn" => magnet.name = Some(decode(&value.replace('+', " "))),
+                "tr" => magnet.add_tracker(&decode(value)),
+                "ws" => magnet.web_seeds.push(decode(value)),
+                "xl" => {
+                    let length = decode(value);
+                    magnet.length = Some(
+                        length
+                            .parse()
+                            .map_err(|_| MagnetError::InvalidLength(length))?,
+                    );
+                }
+                _ => magnet.extra.push((String::from(key), decode(value))),
+            }
+        }
+
+        if magnet.info_hash.is_none() && magnet.info_hash_v2.is_none() {
+            return Err(MagnetError::MissingInfoHash);
+        }
+
+        Ok(magnet)
+    }
+}
+
+impl fmt::Display for Magnet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut params: Vec<String> = Vec::new();
+
+        if let Some(hash) = &self.info_hash {
+            params.push(format!("xt=urn:btih:{}", hash));
+        }
+        if let Some(hash) = &self.info_hash_v2 {
+            params.push(format!("xt=urn:btmh:{}", hash));
+        }
+        if let Some(name) = &self.name {
+            params.push(format!("dn={}", encode(name)));
+        }
+        if let Some(length) = self.length {
+            params.push(format!("xl={}", length));
+        }
+        for tracker in &self.trackers {
+            params.push(format!("tr={}", encode(tracker)));
+        }
+        for web_seed in &self.web_seeds {
+            params.push(format!("ws={}", encode(web_seed)));
+        }
+        for (key, value) in &self.extra {
+            params.push(format!("{}={}", key, encode(value)));
+        }
+
+        write!(f, "magnet:?{}", params.join("&"))
+    }
+}
+
+impl fmt::Display for MagnetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MagnetError::NotAMagnet => write!(f, "link is not a magnet uri"),
+            MagnetError::MissingInfoHash => write!(f, "magnet has no btih or btmh info hash"),
+            MagnetError::InvalidInfoHash(hash) => write!(f, "invalid info hash '{}'", hash),
+            MagnetError::InvalidLength(length) => write!(f, "invalid exact length '{}'", length),
+        }
+    }
+}
+
+impl std::error::Error for MagnetError {}
+
+fn encode(value: &str) -> String {
+    utf8_percent_encode(value, COMPONENT).to_string()
+}
+
+fn decode(value: &str) -> String {
+    percent_decode_str(value).decode_utf8_lossy().into_owned()
+}
+
+// btih is either 40 hex characters or 32 base32 characters
+fn normalize_btih(hash: &str) -> Result<String, MagnetError> {
+    let invalid = || MagnetError::InvalidInfoHash(String::from(hash));
+
+    match hash.len() {
+        40 if hash.chars().all(|c| c.is_ascii_hexdigit()) => Ok(hash.to_lowercase()),
+        32 => {
+            let bytes = decode_base32(hash).ok_or_else(invalid)?;
+            Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+        }
+        _ => Err(invalid()),
+    }
+}
+
+// btmh is a hex multihash, only sha2-256 (0x12, 32 bytes long) is used by bittorrent v2
+fn normalize_btmh(hash: &str) -> Result<String, MagnetError> {
+    let hash_lower = hash.to_lowercase();
+
+    if hash_lower.len() == 68
+        && hash_lower.starts_with("1220")
+        && hash_lower.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        Ok(hash_lower)
+    } else {
+        Err(MagnetError::InvalidInfoHash(String::from(hash)))
+    }
+}
+
+fn decode_base32(input: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer: u64 = 0;
+    let mut bits = 0;
+
+    for c in input.to_ascii_uppercase().bytes() {
+        let value = BASE32_ALPHABET.iter().position(|&a| a == c)? as u64;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(bytes)
+}
@@ -1,28 +1,23 @@
-use clap::{App, Arg};
+use clap::{App, AppSettings, Arg, SubCommand};
+
+use magnetfinder::provider;
 
 fn main() {
-    let matches = App::new("Magnetfinder")
+    let mut app = App::new("Magnetfinder")
         .about("Scrapes torrent links from multiple torrent websites into the terminal")
         .version("1.0")
-        .author("bleusakura")
-        .arg(
-            Arg::with_name("nyaa")
-                .help("scrape nyaa for torrents")
-                .short("n")
-                .long("nyaa"),
-        )
-        .arg(
-            Arg::with_name("piratebay")
-                .help("scrape piratebay for torrents")
-                .short("p")
-                .long("piratebay"),
-        )
-        .arg(
-            Arg::with_name("yts")
-                .help("get torrents from YIFY/YTS")
-                .short("y")
-                .long("yts"),
-        )
+        .author("bleusakura");
+
+    for provider in provider::registry() {
+        app = app.arg(
+            Arg::with_name(provider.name())
+                .help(provider.help())
+                .short(provider.short_flag())
+                .long(provider.name()),
+        );
+    }
+
+    let matches = app
         .arg(
             Arg::with_name("all")
                 .help("scrape from all available implemented websites")
@@ -50,16 +45,56 @@ fn main() {
         )
         .arg(
             Arg::with_name("depth")
-                .help("specifies how many pages to search, default is 1")
+                .help("specifies how many pages to search, or 'all' to search until a site runs out of results, default is 1")
                 .long("depth")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("timeout")
+                .help("seconds to wait for a website before showing results without it, default is 30 (0 waits as long as it takes)")
+                .long("timeout")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("no_cache")
+                .help("fetches every page from the websites without reading or storing cached pages")
+                .long("no-cache"),
+        )
+        .arg(
+            Arg::with_name("refresh")
+                .help("fetches every page from the websites again, replacing the cached pages")
+                .long("refresh")
+                .conflicts_with("no_cache"),
+        )
+        .arg(
+            Arg::with_name("season")
+                .help("only shows torrents of this season or range of seasons (ex: 2, 1-3)")
+                .long("season")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("episode")
+                .help("only shows torrents of this episode or range of episodes (ex: 5, 1-12)")
+                .long("episode")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("packs")
+                .help("also shows season packs and batches containing the episodes searched for")
+                .long("packs"),
+        )
         .arg(
             Arg::with_name("sort")
-                .help("specifies what to sort the torrent table by (size/seeds)")
+                .help("comma separated keys to sort the torrent table by, each optionally followed by :asc or :desc (seeds, size, leechers, date, title, source, ratio, resolution, score, relevance), ex: seeds:desc,size:asc")
                 .long("sort")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("media")
+                .help("type of media searched for, picks the quality profile results are scored with (anime, movie, tvshow)")
+                .long("media")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("proxy")
                 .help("sets a proxy to use when making requests to torrent websites")
@@ -71,12 +106,90 @@ fn main() {
                 .help("disables any interactive features and simply prints all magnet results found to terminal")
                 .long("no-interactive")
         )
+        .arg(
+            Arg::with_name("min_size")
+                .help("hides torrents smaller than this size (ex: 700MB, 1.5GiB)")
+                .long("min-size")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("max_size")
+                .help("hides torrents larger than this size (ex: 4GiB)")
+                .long("max-size")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("min_seeders")
+                .help("hides torrents with fewer seeders than this")
+                .long("min-seeders")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("include")
+                .help("only shows torrents whose title matches this regex (case insensitive)")
+                .long("include")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("exclude")
+                .help("hides torrents whose title matches this regex (case insensitive)")
+                .long("exclude")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("min_resolution")
+                .help("hides torrents with a lower resolution in their title (ex: 720p, 1080p, 4k)")
+                .long("min-resolution")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("exclude_source")
+                .help("hides torrents from these comma separated sources (ex: cam,ts,scr)")
+                .long("exclude-source")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("min_relevance")
+                .help("hides torrents whose title matches the search query less than this, from 0 to 100")
+                .long("min-relevance")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .help("prints every page fetched, its HTTP status and timing, and every mirror skipped after the search")
+                .short("v")
+                .long("verbose"),
+        )
+        .arg(
+            Arg::with_name("tui")
+                .help("browses results in a full screen view with filtering, sorting and multi-select (requires the tui build feature)")
+                .long("tui"),
+        )
+        .arg(
+            Arg::with_name("output")
+                .help("prints results without interacting in this format: text (title and magnet), json, ndjson (one torrent per line) or csv")
+                .long("output")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("format")
+                .help("prints one line per torrent without interacting, filling in {field} placeholders (ex: '{seeders}\\t{size}\\t{title}\\t{magnet}')")
+                .long("format")
+                .takes_value(true)
+                .conflicts_with("output"),
+        )
         .arg(
             Arg::with_name("num_torrents_shown")
                 .help("limits the amount of torrents results shown, useful with --no-interactive")
                 .long("show")
                 .takes_value(true),
         )
+        .subcommand(
+            SubCommand::with_name("cache")
+                .about("manages the pages kept from earlier searches")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(SubCommand::with_name("clear").about("removes every cached page")),
+        )
         .get_matches();
 
     magnetfinder::run(matches);
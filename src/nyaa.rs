@@ -1,78 +1,95 @@
-use std::sync::{mpsc::Sender, Arc};
-use std::thread;
+use chrono::{DateTime, TimeZone, Utc};
+use scraper::{element_ref::ElementRef, Selector};
 
-use scraper::{element_ref::ElementRef, Html, Selector};
-use ureq::Agent;
+use crate::episode::EpisodeNaming;
+use crate::error::Error;
+use crate::magnet::Magnet;
+use crate::provider::{self, Capabilities, ParsedPage, Provider};
+use crate::release::Release;
+use crate::size::Size;
+use crate::{Media, Torrent};
 
-use crate::Torrent;
+// shown instead of the results table when a search found nothing
+const NO_RESULTS: &str = "No results found";
 
-pub fn query(client: &Arc<Agent>, tx: Sender<Vec<Torrent>>, query: &Arc<String>, depth: u32) {
-    for page in 1..=depth {
-        let t_tx = Sender::clone(&tx);
-        let t_client = Arc::clone(client);
-        let t_query = Arc::clone(query);
+// rows on a full results page, shorter pages are the last one
+const PAGE_SIZE: usize = 75;
 
-        thread::spawn(move || {
-            let torrents = fetch_page_results(&t_client, &t_query, page).unwrap_or_else(|err| {
-                eprintln!("Error requesting data from nyaa: {}", err);
-                vec![]
-            });
+pub struct Nyaa;
 
-            t_tx.send(torrents).unwrap();
-        });
+impl Provider for Nyaa {
+    fn name(&self) -> &'static str {
+        "nyaa"
     }
-}
 
-pub fn fetch_page_results(
-    client: &Agent,
-    query: &str,
-    page_number: u32,
-) -> Result<Vec<Torrent>, ureq::Error> {
-    let mut results = Vec::new();
-
-    let formatted_query = query.replace(" ", "+");
-    let url = format!(
-        "https://nyaa.si/?f=0&c=0_0&q={}&s=seeders&o=desc&p={}",
-        formatted_query, page_number
-    );
-    let body = client.get(&url).call()?.into_string()?;
-
-    let document = Html::parse_document(&body);
-    let selector = Selector::parse("tbody tr").unwrap();
-
-    for table_row in document.select(&selector) {
-        let title = match get_title(&table_row) {
-            Some(title) => title,
-            None => continue,
-        };
-        let magnet = match get_magnet(&table_row) {
-            Some(magnet) => magnet,
-            None => continue,
-        };
-        let size = match get_size(&table_row) {
-            Some(size) => size,
-            None => continue,
-        };
-        let seeders = match get_seeders(&table_row) {
-            Some(seeders) => seeders,
-            None => continue,
-        };
+    fn short_flag(&self) -> &'static str {
+        "n"
+    }
 
-        results.push(Torrent {
-            title,
-            magnet,
-            size,
-            seeders,
-        });
+    fn help(&self) -> &'static str {
+        "scrape nyaa for torrents"
     }
 
-    Ok(results)
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            media: &[Media::Anime],
+            episode_naming: Some(EpisodeNaming::Fansub),
+        }
+    }
+
+    fn default_mirrors(&self) -> &'static [&'static str] {
+        &["https://nyaa.si"]
+    }
+
+    fn search_url(&self, mirror: &str, query: &str, page_number: u32) -> String {
+        let formatted_query = query.replace(" ", "+");
+        format!(
+            "{}/?f=0&c=0_0&q={}&s=seeders&o=desc&p={}",
+            mirror, formatted_query, page_number
+        )
+    }
+
+    fn parse_page(&self, body: &str) -> Result<ParsedPage, Error> {
+        parse_page(body)
+    }
+}
+
+pub fn parse_page(body: &str) -> Result<ParsedPage, Error> {
+    provider::parse_rows(body, NO_RESULTS, PAGE_SIZE, parse_row)
+}
+
+// None when any required column is missing or unreadable
+fn parse_row(table_row: &ElementRef) -> Option<Torrent> {
+    let title = get_title(table_row)?;
+    let magnet = get_magnet(table_row)?;
+    let size = get_size(table_row)?;
+    let seeders = get_peers(table_row, 3)?;
+    let leechers = get_peers(table_row, 4)?;
+
+    Some(Torrent {
+        info_hash: magnet.key().map(String::from),
+        release: Release::parse(&title),
+        title,
+        magnet: magnet.to_string(),
+        size,
+        seeders,
+        leechers,
+        uploaded: get_uploaded(table_row),
+        // uploaders are only listed on a torrent's detail page
+        uploader: None,
+        category: get_category(table_row),
+        sources: vec!["nyaa"],
+        url: get_url(table_row),
+        score: 0,
+        relevance: 0,
+    })
 }
 
 fn get_title(table_row: &ElementRef) -> Option<String> {
-    let selector = Selector::parse("td[colspan] a").unwrap();
+    // rows with comments have an extra comment count link before the title
+    let selector = Selector::parse("td[colspan] a:not(.comments)").unwrap();
 
-    let title = match table_row.select(&selector).nth(1) {
+    let title = match table_row.select(&selector).next() {
         Some(t) => t.value().attr("title").unwrap_or(""),
         None => return None,
     };
@@ -84,24 +101,23 @@ fn get_title(table_row: &ElementRef) -> Option<String> {
     Some(String::from(title))
 }
 
-fn _get_title_verbose(query: &str, table_row: &ElementRef) -> Option<String> {
-    let selector = Selector::parse("td[colspan] a").unwrap();
+fn get_url(table_row: &ElementRef) -> Option<String> {
+    let selector = Selector::parse("td[colspan] a:not(.comments)").unwrap();
 
-    for data in table_row.select(&selector) {
-        let title = match data.value().attr("title") {
-            Some(t) => t,
-            None => continue,
-        };
+    let url = table_row.select(&selector).next()?.value().attr("href")?;
 
-        if title.to_lowercase().contains(query) {
-            return Some(String::from(title));
-        }
-    }
+    Some(String::from(url))
+}
 
-    None
+fn get_category(table_row: &ElementRef) -> Option<String> {
+    let selector = Selector::parse("td a[title]").unwrap();
+
+    let category = table_row.select(&selector).next()?.value().attr("title")?;
+
+    Some(String::from(category))
 }
 
-fn get_magnet(table_row: &ElementRef) -> Option<String> {
+fn get_magnet(table_row: &ElementRef) -> Option<Magnet> {
     let selector = Selector::parse("td.text-center a").unwrap();
 
     for data in table_row.select(&selector) {
@@ -110,15 +126,15 @@ fn get_magnet(table_row: &ElementRef) -> Option<String> {
             None => continue,
         };
 
-        if magnet.contains("magnet") {
-            return Some(String::from(magnet));
+        if let Ok(magnet) = magnet.parse() {
+            return Some(magnet);
         }
     }
 
     None
 }
 
-fn get_size(table_row: &ElementRef) -> Option<String> {
+fn get_size(table_row: &ElementRef) -> Option<Size> {
     let selector = Selector::parse("td.text-center").unwrap();
 
     let size = match table_row.select(&selector).nth(1) {
@@ -126,16 +142,31 @@ fn get_size(table_row: &ElementRef) -> Option<String> {
         None => return None,
     };
 
-    Some(size)
+    size.parse().ok()
+}
+
+fn get_uploaded(table_row: &ElementRef) -> Option<DateTime<Utc>> {
+    let selector = Selector::parse("td[data-timestamp]").unwrap();
+
+    let timestamp: i64 = table_row
+        .select(&selector)
+        .next()?
+        .value()
+        .attr("data-timestamp")?
+        .parse()
+        .ok()?;
+
+    Utc.timestamp_opt(timestamp, 0).single()
 }
 
-fn get_seeders(table_row: &ElementRef) -> Option<String> {
+// seeders and leechers are the 4th and 5th centered columns
+fn get_peers(table_row: &ElementRef, column: usize) -> Option<u32> {
     let selector = Selector::parse("td.text-center").unwrap();
 
-    let seeders = match table_row.select(&selector).nth(3) {
+    let peers = match table_row.select(&selector).nth(column) {
         Some(s) => s.inner_html(),
         None => return None,
     };
 
-    Some(seeders)
+    peers.trim().parse().ok()
 }
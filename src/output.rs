@@ -0,0 +1,370 @@
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::{OutputFormat, Torrent};
+
+// torrent values that can be used in --format templates and csv columns
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Field {
+    Title,
+    Magnet,
+    Size,
+    SizeBytes,
+    Seeders,
+    Leechers,
+    Uploaded,
+    Date,
+    Uploader,
+    Category,
+    Sources,
+    InfoHash,
+    Url,
+    Score,
+    Relevance,
+    Quality,
+    Resolution,
+    Source,
+    VideoCodec,
+    AudioCodec,
+    Group,
+    Season,
+    Episodes,
+    Year,
+    Languages,
+}
+
+// csv columns, in order
+pub const FIELDS: [Field; 25] = [
+    Field::Title,
+    Field::Magnet,
+    Field::Size,
+    Field::SizeBytes,
+    Field::Seeders,
+    Field::Leechers,
+    Field::Uploaded,
+    Field::Date,
+    Field::Uploader,
+    Field::Category,
+    Field::Sources,
+    Field::InfoHash,
+    Field::Url,
+    Field::Score,
+    Field::Relevance,
+    Field::Quality,
+    Field::Resolution,
+    Field::Source,
+    Field::VideoCodec,
+    Field::AudioCodec,
+    Field::Group,
+    Field::Season,
+    Field::Episodes,
+    Field::Year,
+    Field::Languages,
+];
+
+#[derive(Clone, PartialEq, Debug)]
+enum Segment {
+    Literal(String),
+    Field(Field),
+}
+
+// a --format string such as "{seeders}\t{size}\t{title}\t{magnet}"
+#[derive(Clone, PartialEq, Debug)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Field {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::Title => "title",
+            Field::Magnet => "magnet",
+            Field::Size => "size",
+            Field::SizeBytes => "size_bytes",
+            Field::Seeders => "seeders",
+            Field::Leechers => "leechers",
+            Field::Uploaded => "uploaded",
+            Field::Date => "date",
+            Field::Uploader => "uploader",
+            Field::Category => "category",
+            Field::Sources => "sources",
+            Field::InfoHash => "info_hash",
+            Field::Url => "url",
+            Field::Score => "score",
+            Field::Relevance => "relevance",
+            Field::Quality => "quality",
+            Field::Resolution => "resolution",
+            Field::Source => "source",
+            Field::VideoCodec => "video_codec",
+            Field::AudioCodec => "audio_codec",
+            Field::Group => "group",
+            Field::Season => "season",
+            Field::Episodes => "episodes",
+            Field::Year => "year",
+            Field::Languages => "languages",
+        }
+    }
+
+    // missing values are empty strings
+    pub fn value(&self, torrent: &Torrent) -> String {
+        let release = &torrent.release;
+
+        match self {
+            Field::Title => torrent.title.clone(),
+            Field::Magnet => torrent.magnet.clone(),
+            Field::Size => torrent.size.to_string(),
+            Field::SizeBytes => torrent.size.bytes().to_string(),
+            Field::Seeders => torrent.seeders.to_string(),
+            Field::Leechers => torrent.leechers.to_string(),
+            Field::Uploaded => optional(torrent.uploaded.map(|date| date.to_rfc3339())),
+            Field::Date => optional(torrent.uploaded.map(|date| date.format("%Y-%m-%d"))),
+            Field::Uploader => optional(torrent.uploader.as_ref()),
+            Field::Category => optional(torrent.category.as_ref()),
+            Field::Sources => torrent.sources.join(", "),
+            Field::InfoHash => optional(torrent.info_hash.as_ref()),
+            Field::Url => optional(torrent.url.as_ref()),
+            Field::Score => torrent.score.to_string(),
+            Field::Relevance => torrent.relevance.to_string(),
+            Field::Quality => release.summary(),
+            Field::Resolution => optional(release.resolution.map(|r| format!("{}p", r))),
+            Field::Source => optional(release.source),
+            Field::VideoCodec => optional(release.video_codec),
+            Field::AudioCodec => optional(release.audio_codec),
+            Field::Group => optional(release.group.as_ref()),
+            Field::Season => match (release.season, release.last_season) {
+                (Some(first), Some(last)) => format!("{}-{}", first, last),
+                (season, _) => optional(season),
+            },
+            Field::Episodes => join(&release.episodes),
+            Field::Year => optional(release.year),
+            Field::Languages => release.languages.join(", "),
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FIELDS
+            .iter()
+            .find(|field| field.name() == s.trim())
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = FIELDS.iter().map(Field::name).collect();
+                format!(
+                    "Unknown field '{}', supported fields: {}",
+                    s.trim(),
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    // fields are written as {name}, "{{" and "}}" are literal braces and \t, \n and \\ are
+    // unescaped since shells pass them through as is
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = s.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(format!("Unclosed '{{{}' in format", name)),
+                        }
+                    }
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(literal.split_off(0)));
+                    }
+                    segments.push(Segment::Field(name.parse()?));
+                }
+                '}' => {
+                    return Err(String::from(
+                        "Unmatched '}' in format, use '}}' for a brace",
+                    ))
+                }
+                '\\' => match chars.peek() {
+                    Some('t') => {
+                        chars.next();
+                        literal.push('\t');
+                    }
+                    Some('n') => {
+                        chars.next();
+                        literal.push('\n');
+                    }
+                    Some('\\') => {
+                        chars.next();
+                        literal.push('\\');
+                    }
+                    _ => literal.push('\\'),
+                },
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Template { segments })
+    }
+}
+
+impl Template {
+    // tabs and newlines inside values are replaced so each torrent stays on one line
+    pub fn render(&self, torrent: &Torrent) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(literal) => literal.clone(),
+                Segment::Field(field) => single_line(&field.value(torrent)),
+            })
+            .collect()
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" | "jsonl" => Ok(OutputFormat::Ndjson),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(format!(
+                "Unknown output format '{}', supported formats: text, json, ndjson, csv",
+                s.trim()
+            )),
+        }
+    }
+}
+
+// writes the results of a --no-interactive search
+pub fn write_torrents<W: Write>(
+    out: &mut W,
+    torrents: &[Torrent],
+    format: &OutputFormat,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Text => {
+            for torrent in torrents {
+                // tabs and newlines in titles would break the two column layout
+                writeln!(out, "{}\t{}", single_line(&torrent.title), torrent.magnet)?;
+            }
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer(&mut *out, torrents)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Ndjson => {
+            for torrent in torrents {
+                serde_json::to_writer(&mut *out, torrent)?;
+                writeln!(out)?;
+            }
+        }
+        OutputFormat::Csv => {
+            let header: Vec<&str> = FIELDS.iter().map(Field::name).collect();
+            writeln!(out, "{}", header.join(","))?;
+
+            for torrent in torrents {
+                let row: Vec<String> = FIELDS
+                    .iter()
+                    .map(|field| csv_quote(&field.value(torrent)))
+                    .collect();
+                writeln!(out, "{}", row.join(","))?;
+            }
+        }
+        OutputFormat::Template(template) => {
+            for torrent in torrents {
+                writeln!(out, "{}", template.render(torrent))?;
+            }
+        }
+    }
+
+    out.flush()
+}
+
+// the full description of one torrent, shown when inspecting a result
+pub fn details(torrent: &Torrent) -> Vec<String> {
+    let release = &torrent.release;
+    let or_dash = |value: String| {
+        if value.is_empty() {
+            String::from("-")
+        } else {
+            value
+        }
+    };
+    let value = |field: Field| or_dash(field.value(torrent));
+
+    vec![
+        torrent.title.clone(),
+        format!(
+            "{} | {} seeders, {} leechers | uploaded {} by {} | {}",
+            torrent.size,
+            torrent.seeders,
+            torrent.leechers,
+            or_dash(optional(
+                torrent.uploaded.map(|d| d.format("%Y-%m-%d %H:%M"))
+            )),
+            value(Field::Uploader),
+            value(Field::Category),
+        ),
+        format!(
+            "quality: {} | group: {} | season: {} | episodes: {} | languages: {}",
+            or_dash(release.summary()),
+            value(Field::Group),
+            value(Field::Season),
+            value(Field::Episodes),
+            value(Field::Languages),
+        ),
+        format!(
+            "score: {} | relevance: {} | sites: {}",
+            torrent.score,
+            torrent.relevance,
+            torrent.sources.join(", ")
+        ),
+        format!("hash: {}", value(Field::InfoHash)),
+        format!("page: {}", value(Field::Url)),
+    ]
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn join(numbers: &[u32]) -> String {
+    let numbers: Vec<String> = numbers.iter().map(u32::to_string).collect();
+    numbers.join(",")
+}
+
+fn single_line(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+// quotes values containing separators, quotes or line breaks as described in RFC 4180
+fn csv_quote(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        String::from(value)
+    }
+}
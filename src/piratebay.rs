@@ -1,72 +1,85 @@
-use std::sync::{mpsc::Sender, Arc};
-use std::thread;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use scraper::{element_ref::ElementRef, Selector};
 
-use scraper::{element_ref::ElementRef, Html, Selector};
-use ureq::Agent;
+use crate::episode::EpisodeNaming;
+use crate::error::Error;
+use crate::magnet::Magnet;
+use crate::provider::{self, Capabilities, ParsedPage, Provider};
+use crate::release::Release;
+use crate::size::Size;
+use crate::{Media, Torrent};
 
-use crate::Torrent;
+// shown instead of the results table when a search found nothing
+const NO_RESULTS: &str = "No hits.";
 
-pub fn query(client: &Arc<Agent>, tx: Sender<Vec<Torrent>>, query: &Arc<String>, depth: u32) {
-    for page in 1..=depth {
-        let t_tx = Sender::clone(&tx);
-        let t_client = Arc::clone(client);
-        let t_query = Arc::clone(query);
+// rows on a full results page, shorter pages are the last one
+const PAGE_SIZE: usize = 30;
 
-        thread::spawn(move || {
-            let torrents = fetch_page_results(&t_client, &t_query, page).unwrap_or_else(|err| {
-                eprintln!("Error requesting data from piratebay: {}", err);
-                vec![]
-            });
+pub struct Piratebay;
 
-            t_tx.send(torrents).unwrap();
-        });
+impl Provider for Piratebay {
+    fn name(&self) -> &'static str {
+        "piratebay"
+    }
+
+    fn short_flag(&self) -> &'static str {
+        "p"
+    }
+
+    fn help(&self) -> &'static str {
+        "scrape piratebay for torrents"
     }
-}
 
-pub fn fetch_page_results(
-    client: &Agent,
-    query: &str,
-    page_number: u32,
-) -> Result<Vec<Torrent>, ureq::Error> {
-    let mut results = Vec::new();
-
-    let formatted_query = query.replace(" ", "%20");
-    let url = format!(
-        "https://www.tpb.party/search/{}/{}/99/0",
-        formatted_query, page_number
-    );
-    let body = client.get(&url).call()?.into_string()?;
-
-    let document = Html::parse_document(&body);
-    let selector = Selector::parse("tbody tr").unwrap();
-
-    for table_row in document.select(&selector) {
-        let title = match get_title(&table_row) {
-            Some(title) => title,
-            None => continue,
-        };
-        let magnet = match get_magnet(&table_row) {
-            Some(magnet) => magnet,
-            None => continue,
-        };
-        let size = match get_size(&table_row) {
-            Some(size) => size,
-            None => continue,
-        };
-        let seeders = match get_seeders(&table_row) {
-            Some(seeders) => seeders,
-            None => continue,
-        };
-
-        results.push(Torrent {
-            title,
-            magnet,
-            size,
-            seeders,
-        });
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            media: &[Media::Anime, Media::Movie, Media::TVShow],
+            episode_naming: Some(EpisodeNaming::Scene),
+        }
     }
 
-    Ok(results)
+    fn default_mirrors(&self) -> &'static [&'static str] {
+        &["https://www.tpb.party"]
+    }
+
+    fn search_url(&self, mirror: &str, query: &str, page_number: u32) -> String {
+        let formatted_query = query.replace(" ", "%20");
+        format!("{}/search/{}/{}/99/0", mirror, formatted_query, page_number)
+    }
+
+    fn parse_page(&self, body: &str) -> Result<ParsedPage, Error> {
+        parse_page(body)
+    }
+}
+
+pub fn parse_page(body: &str) -> Result<ParsedPage, Error> {
+    provider::parse_rows(body, NO_RESULTS, PAGE_SIZE, parse_row)
+}
+
+// None when any required column is missing or unreadable
+fn parse_row(table_row: &ElementRef) -> Option<Torrent> {
+    let title = get_title(table_row)?;
+    let magnet = get_magnet(table_row)?;
+    let description = get_description(table_row)?;
+    let size = get_size(&description)?;
+    let seeders = get_peers(table_row, 2)?;
+    let leechers = get_peers(table_row, 3)?;
+
+    Some(Torrent {
+        info_hash: magnet.key().map(String::from),
+        release: Release::parse(&title),
+        title,
+        magnet: magnet.to_string(),
+        size,
+        seeders,
+        leechers,
+        uploaded: get_uploaded(&description, Utc::now()),
+        uploader: get_uploader(table_row),
+        category: get_category(table_row),
+        sources: vec!["piratebay"],
+        url: get_url(table_row),
+        score: 0,
+        relevance: 0,
+    })
 }
 
 fn get_title(table_row: &ElementRef) -> Option<String> {
@@ -84,51 +97,117 @@ fn get_title(table_row: &ElementRef) -> Option<String> {
     Some(title)
 }
 
-fn get_magnet(table_row: &ElementRef) -> Option<String> {
+fn get_url(table_row: &ElementRef) -> Option<String> {
+    let selector = Selector::parse(".detLink").unwrap();
+
+    let url = table_row.select(&selector).next()?.value().attr("href")?;
+
+    Some(String::from(url))
+}
+
+fn get_magnet(table_row: &ElementRef) -> Option<Magnet> {
     let selector = Selector::parse("[alt='Magnet link']").unwrap();
 
     let magnet = match table_row.select(&selector).next() {
         Some(p) => match p.parent() {
-            Some(parent) => match parent.value().as_element().unwrap().attr("href") {
-                Some(m) => m,
-                None => return None,
-            },
+            Some(parent) => parent.value().as_element().unwrap().attr("href")?,
             None => return None,
         },
         None => return None,
     };
 
-    if magnet.contains("magnet") {
-        return Some(String::from(magnet));
+    magnet.parse().ok()
+}
+
+fn get_category(table_row: &ElementRef) -> Option<String> {
+    let selector = Selector::parse("td.vertTh a").unwrap();
+
+    let category: Vec<String> = table_row
+        .select(&selector)
+        .map(|a| a.inner_html())
+        .collect();
+
+    if category.is_empty() {
+        return None;
     }
 
-    None
+    Some(category.join(" > "))
 }
 
-fn get_size(table_row: &ElementRef) -> Option<String> {
-    let selector = Selector::parse(".detDesc").unwrap();
+// eg. "Uploaded 02-03&nbsp;2021, Size 2.45&nbsp;GiB, ULed by <a ...>eztv</a>"
+fn get_description(table_row: &ElementRef) -> Option<Vec<String>> {
+    let selector = Selector::parse("font.detDesc").unwrap();
 
     let desc = match table_row.select(&selector).next() {
         Some(s) => s.inner_html(),
         None => return None,
     };
 
-    let split: Vec<&str> = desc.split(", ").collect();
-    let size = match split.get(1) {
-        Some(s) => s.replace("Size ", "").replace("&nbsp;", " "),
-        None => return None,
+    Some(
+        desc.replace("&nbsp;", " ")
+            .split(", ")
+            .map(String::from)
+            .collect(),
+    )
+}
+
+fn get_size(description: &[String]) -> Option<Size> {
+    let size = description.get(1)?.strip_prefix("Size ")?;
+
+    size.parse().ok()
+}
+
+// piratebay shows recent uploads relative to today and leaves out the year for this year's
+fn get_uploaded(description: &[String], now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let uploaded = description.first()?.strip_prefix("Uploaded ")?;
+    let uploaded = uploaded.replace("<b>", "").replace("</b>", "");
+    let (day, time) = uploaded.split_once(' ')?;
+
+    if time.ends_with("ago") {
+        let minutes: i64 = day.parse().ok()?;
+        return Some(now - Duration::minutes(minutes));
+    }
+
+    let date = match day {
+        "Today" => now.date_naive(),
+        "Y-day" => now.date_naive() - Duration::days(1),
+        _ => {
+            let (month, day_of_month) = day.split_once('-')?;
+            let month: u32 = month.parse().ok()?;
+            let day_of_month: u32 = day_of_month.parse().ok()?;
+
+            match time.parse::<i32>() {
+                Ok(year) => {
+                    return NaiveDate::from_ymd_opt(year, month, day_of_month)
+                        .and_then(|date| date.and_hms_opt(0, 0, 0))
+                        .map(|date| Utc.from_utc_datetime(&date))
+                }
+                Err(_) => NaiveDate::from_ymd_opt(now.year(), month, day_of_month)?,
+            }
+        }
     };
 
-    Some(size)
+    let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+
+    Some(Utc.from_utc_datetime(&date.and_time(time)))
+}
+
+fn get_uploader(table_row: &ElementRef) -> Option<String> {
+    let selector = Selector::parse("font.detDesc a.detDesc, font.detDesc i").unwrap();
+
+    let uploader = table_row.select(&selector).next()?.inner_html();
+
+    Some(uploader)
 }
 
-fn get_seeders(table_row: &ElementRef) -> Option<String> {
+// seeders and leechers are the 3rd and 4th columns
+fn get_peers(table_row: &ElementRef, column: usize) -> Option<u32> {
     let selector = Selector::parse("td").unwrap();
 
-    let seeders = match table_row.select(&selector).nth(2) {
+    let peers = match table_row.select(&selector).nth(column) {
         Some(s) => s.inner_html(),
         None => return None,
     };
 
-    Some(seeders)
+    peers.trim().parse().ok()
 }
@@ -0,0 +1,111 @@
+use crate::release::{Source, VideoCodec};
+use crate::size::Size;
+use crate::{Media, Torrent};
+
+// points given to the most preferred entry of each list, later entries get a share of it
+const RESOLUTION_POINTS: i32 = 100;
+const CODEC_POINTS: i32 = 50;
+const GROUP_POINTS: i32 = 50;
+
+#[derive(Clone, Default)]
+pub struct Profile {
+    // preferences are listed most preferred first
+    pub resolutions: Vec<u32>,
+    pub codecs: Vec<VideoCodec>,
+    pub groups: Vec<String>,
+    // results matching any of these are dropped
+    pub forbidden_words: Vec<String>,
+    pub forbidden_sources: Vec<Source>,
+    pub min_size: Option<Size>,
+    pub max_size: Option<Size>,
+}
+
+#[derive(Clone, Default)]
+pub struct Profiles {
+    pub anime: Profile,
+    pub movie: Profile,
+    pub tvshow: Profile,
+}
+
+impl Profiles {
+    pub fn for_media(&self, media: Media) -> &Profile {
+        match media {
+            Media::Anime => &self.anime,
+            Media::Movie => &self.movie,
+            Media::TVShow => &self.tvshow,
+        }
+    }
+}
+
+impl Profile {
+    pub fn is_rejected(&self, torrent: &Torrent) -> bool {
+        if let Some(min_size) = self.min_size {
+            if torrent.size < min_size {
+                return true;
+            }
+        }
+        if let Some(max_size) = self.max_size {
+            if torrent.size > max_size {
+                return true;
+            }
+        }
+        if let Some(source) = torrent.release.source {
+            if self.forbidden_sources.contains(&source) {
+                return true;
+            }
+        }
+
+        let title = words_of(&torrent.title);
+        self.forbidden_words
+            .iter()
+            .any(|forbidden| contains_words(&title, &words_of(forbidden)))
+    }
+
+    pub fn score(&self, torrent: &Torrent) -> i32 {
+        let release = &torrent.release;
+
+        let resolution = release
+            .resolution
+            .and_then(|r| self.resolutions.iter().position(|p| *p == r));
+        let codec = release
+            .video_codec
+            .and_then(|c| self.codecs.iter().position(|p| *p == c));
+        let group = release.group.as_ref().and_then(|g| {
+            self.groups
+                .iter()
+                .position(|p| p.eq_ignore_ascii_case(g.trim()))
+        });
+
+        rank_points(resolution, self.resolutions.len(), RESOLUTION_POINTS)
+            + rank_points(codec, self.codecs.len(), CODEC_POINTS)
+            + rank_points(group, self.groups.len(), GROUP_POINTS)
+    }
+
+    // drops rejected torrents and scores the rest
+    pub fn apply(&self, torrents: &mut Vec<Torrent>) {
+        torrents.retain(|torrent| !self.is_rejected(torrent));
+        for torrent in torrents {
+            torrent.score = self.score(torrent);
+        }
+    }
+}
+
+// the first of n preferences is worth all the points, the last 1/n of them
+fn rank_points(position: Option<usize>, len: usize, points: i32) -> i32 {
+    match position {
+        Some(i) => points * (len - i) as i32 / len as i32,
+        None => 0,
+    }
+}
+
+fn words_of(s: &str) -> Vec<String> {
+    s.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+// whole word matching so "CAM" doesn't forbid "Cameron", "dual audio" matches "Dual.Audio"
+fn contains_words(title: &[String], words: &[String]) -> bool {
+    !words.is_empty() && title.windows(words.len()).any(|window| window == words)
+}
@@ -0,0 +1,330 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
+
+use scraper::{element_ref::ElementRef, Html, Selector};
+
+use crate::episode::EpisodeNaming;
+use crate::error::Error;
+use crate::http::Client;
+use crate::limits::Pool;
+use crate::nyaa::Nyaa;
+use crate::piratebay::Piratebay;
+use crate::search::{Page, Pages};
+use crate::yts::Yts;
+use crate::{Media, Torrent};
+
+// result rows of the html sites' tables
+const ROW_SELECTOR: &str = "tbody tr";
+
+// markers found on cloudflare/ddos-guard challenge pages served instead of results
+const BLOCK_PAGE_MARKERS: [&str; 5] = [
+    "cf-browser-verification",
+    "cf-challenge",
+    "<title>Just a moment...</title>",
+    "<title>Attention Required! | Cloudflare</title>",
+    "<title>DDoS-Guard</title>",
+];
+
+pub struct Capabilities {
+    // types of media the site carries, used to skip sites that can't match a search
+    pub media: &'static [Media],
+    // None for sites without episodic releases, which are skipped by episode searches
+    pub episode_naming: Option<EpisodeNaming>,
+}
+
+// the results read from one page, rows that couldn't be read are counted instead
+#[derive(Default)]
+pub struct ParsedPage {
+    pub torrents: Vec<Torrent>,
+    pub skipped: usize,
+    // the site has no results past this page, eg. it wasn't full
+    pub last: bool,
+}
+
+// a page that was fetched and parsed, along with where it came from
+pub struct Fetched {
+    pub page: ParsedPage,
+    pub mirror: String,
+    pub status: u16,
+    // mirrors tried before this one, with why they were skipped
+    pub failovers: Vec<String>,
+    // read from the cache instead of the site
+    pub cached: bool,
+}
+
+pub trait Provider: Send + Sync {
+    // doubles as the long cli flag and the name typed in interactive mode
+    fn name(&self) -> &'static str;
+
+    fn short_flag(&self) -> &'static str;
+
+    fn help(&self) -> &'static str;
+
+    fn capabilities(&self) -> Capabilities;
+
+    // used when Settings.toml doesn't list any mirrors for this site
+    fn default_mirrors(&self) -> &'static [&'static str];
+
+    fn search_url(&self, mirror: &str, query: &str, page_number: u32) -> String;
+
+    fn parse_page(&self, body: &str) -> Result<ParsedPage, Error>;
+
+    // tries each mirror in order, moving on when one is unreachable or blocked, pages
+    // still in the cache are read from there instead
+    fn fetch_page(
+        &self,
+        client: &Client,
+        mirrors: &[String],
+        query: &str,
+        page_number: u32,
+    ) -> Result<Fetched, Error> {
+        let mut failovers = Vec::new();
+        let mut last_err = None;
+
+        for mirror in mirrors {
+            let mirror = mirror.trim_end_matches('/');
+            let url = self.search_url(mirror, query, page_number);
+
+            let result = match client.cache().and_then(|cache| cache.get(&url)) {
+                Some((status, body)) => self.parse_page(&body).map(|page| (status, page, true)),
+                None => fetch_body(client, &url).and_then(|(status, body)| {
+                    let page = self.parse_page(&body)?;
+                    // only pages that could be read are kept
+                    if let Some(cache) = client.cache() {
+                        cache.put(&url, status, &body);
+                    }
+                    Ok((status, page, false))
+                }),
+            };
+
+            match result {
+                Ok((status, page, cached)) => {
+                    return Ok(fetched(mirror, page, status, failovers, cached))
+                }
+                Err(err) if should_failover(&err) => {
+                    failovers.push(format!("{}: {}", mirror, err));
+                    last_err = Some(err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        // every mirror failed, eg. when offline, so the last copy of the page stands in
+        if let Some(cache) = client.cache() {
+            for mirror in mirrors {
+                let mirror = mirror.trim_end_matches('/');
+                let stale = cache
+                    .get_stale(&self.search_url(mirror, query, page_number))
+                    .and_then(|(status, body)| Some((status, self.parse_page(&body).ok()?)));
+
+                if let Some((status, page)) = stale {
+                    return Ok(fetched(mirror, page, status, failovers, true));
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or(Error::NoMirrors(self.name())))
+    }
+}
+
+// every implemented website, adding a new site only requires adding it here
+pub fn registry() -> Vec<Arc<dyn Provider>> {
+    vec![Arc::new(Nyaa), Arc::new(Piratebay), Arc::new(Yts)]
+}
+
+pub fn find(name: &str) -> Option<Arc<dyn Provider>> {
+    registry()
+        .into_iter()
+        .find(|provider| provider.name() == name)
+}
+
+pub fn names() -> Vec<&'static str> {
+    registry().iter().map(|provider| provider.name()).collect()
+}
+
+// mirrors configured in Settings.toml take priority over the provider's defaults
+pub fn mirrors(
+    provider: &Arc<dyn Provider>,
+    configured: &HashMap<String, Vec<String>>,
+) -> Arc<Vec<String>> {
+    match configured.get(provider.name()) {
+        Some(mirrors) if !mirrors.is_empty() => Arc::new(mirrors.clone()),
+        _ => Arc::new(
+            provider
+                .default_mirrors()
+                .iter()
+                .map(|mirror| String::from(*mirror))
+                .collect(),
+        ),
+    }
+}
+
+// fetches one page after the other until the site runs out of results or depth pages were
+// read, None reads every page there is. each page is its own job queued behind the pages
+// already waiting, so a deep search takes turns with the other sites instead of holding a worker
+pub fn query(
+    pool: &Pool,
+    provider: &Arc<dyn Provider>,
+    client: &Arc<Client>,
+    mirrors: &Arc<Vec<String>>,
+    pages: Pages,
+    query: &Arc<String>,
+    depth: Option<u32>,
+) {
+    let paging = Paging {
+        pool: pool.clone(),
+        provider: Arc::clone(provider),
+        client: Arc::clone(client),
+        mirrors: Arc::clone(mirrors),
+        pages,
+        query: Arc::clone(query),
+        depth,
+        seen: HashSet::new(),
+    };
+
+    pool.execute(move || fetch_pages(paging, 1));
+}
+
+// everything a query needs to fetch its next page
+struct Paging {
+    pool: Pool,
+    provider: Arc<dyn Provider>,
+    client: Arc<Client>,
+    mirrors: Arc<Vec<String>>,
+    pages: Pages,
+    query: Arc<String>,
+    depth: Option<u32>,
+    // some sites keep serving their last page for any page past it
+    seen: HashSet<String>,
+}
+
+fn fetch_pages(mut paging: Paging, number: u32) {
+    // the site timed out while this page waited its turn
+    if paging.pages.is_cancelled() {
+        return;
+    }
+
+    let started = Instant::now();
+    let result = paging
+        .provider
+        .fetch_page(&paging.client, &paging.mirrors, &paging.query, number);
+
+    let last = match &result {
+        Ok(fetched) => {
+            let new = fetched
+                .page
+                .torrents
+                .iter()
+                .filter(|torrent| paging.seen.insert(torrent.magnet.clone()))
+                .count();
+            fetched.page.last || new == 0 || paging.depth.is_some_and(|depth| number >= depth)
+        }
+        Err(_) => true,
+    };
+
+    let page = Page {
+        provider: paging.provider.name(),
+        number,
+        result,
+        latency: started.elapsed(),
+        last,
+    };
+    // the search stops listening once a selection was made or the site timed out
+    if !paging.pages.send(page) || last {
+        return;
+    }
+
+    let pool = paging.pool.clone();
+    pool.execute(move || fetch_pages(paging, number + 1));
+}
+
+fn fetched(
+    mirror: &str,
+    mut page: ParsedPage,
+    status: u16,
+    failovers: Vec<String>,
+    cached: bool,
+) -> Fetched {
+    for torrent in &mut page.torrents {
+        if let Some(path) = torrent.url.as_ref().filter(|u| u.starts_with('/')) {
+            torrent.url = Some(format!("{}{}", mirror, path));
+        }
+    }
+
+    Fetched {
+        page,
+        mirror: String::from(mirror),
+        status,
+        failovers,
+        cached,
+    }
+}
+
+fn fetch_body(client: &Client, url: &str) -> Result<(u16, String), Error> {
+    let response = client.get(url)?;
+    let status = response.status();
+    let body = response
+        .into_string()
+        .map_err(|err| Error::Network(format!("{}: failed to read response: {}", url, err)))?;
+
+    if is_block_page(&body) {
+        return Err(Error::Blocked(String::from(url)));
+    }
+
+    Ok((status, body))
+}
+
+pub fn is_block_page(body: &str) -> bool {
+    BLOCK_PAGE_MARKERS
+        .iter()
+        .any(|marker| body.contains(marker))
+}
+
+// reads every result row of an html results page, no_results is the text the site shows
+// instead when a search found nothing and page_size the rows on a full page
+pub fn parse_rows(
+    body: &str,
+    no_results: &str,
+    page_size: usize,
+    parse_row: fn(&ElementRef) -> Option<Torrent>,
+) -> Result<ParsedPage, Error> {
+    let mut page = ParsedPage::default();
+
+    let document = Html::parse_document(body);
+    let selector = Selector::parse(ROW_SELECTOR).unwrap();
+
+    for table_row in document.select(&selector) {
+        match parse_row(&table_row) {
+            Some(torrent) => page.torrents.push(torrent),
+            None => page.skipped += 1,
+        }
+    }
+
+    let rows = page.torrents.len() + page.skipped;
+    if rows == 0 && !body.contains(no_results) {
+        return Err(Error::LayoutChanged {
+            selector: ROW_SELECTOR,
+        });
+    }
+    if rows > 0 && page.torrents.is_empty() {
+        return Err(Error::PartialRows {
+            skipped: page.skipped,
+            rows,
+        });
+    }
+    page.last = rows < page_size;
+
+    Ok(page)
+}
+
+fn should_failover(err: &Error) -> bool {
+    match err {
+        Error::Network(_) | Error::Blocked(_) => true,
+        // cloudflare answers challenges with 403/503, dead mirrors tend to 5xx
+        Error::Status { code, .. } => *code == 403 || *code == 429 || *code >= 500,
+        // parked or hijacked mirrors serve some other page, another mirror might have results
+        Error::Json(_) | Error::LayoutChanged { .. } => true,
+        Error::PartialRows { .. } | Error::NoMirrors(_) => false,
+    }
+}
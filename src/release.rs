@@ -0,0 +1,463 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use regex::{Captures, Regex, RegexBuilder};
+use serde::{Serialize, Serializer};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Source {
+    Cam,
+    Telesync,
+    Screener,
+    Dvd,
+    Hdtv,
+    WebRip,
+    WebDl,
+    BluRay,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VideoCodec {
+    H264,
+    H265,
+    AV1,
+    VP9,
+    XviD,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AudioCodec {
+    TrueHD,
+    DTSHD,
+    DTS,
+    EAC3,
+    AC3,
+    FLAC,
+    Opus,
+    AAC,
+    MP3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hdr {
+    Hdr10,
+    Hdr10Plus,
+    DolbyVision,
+    Hlg,
+}
+
+// details parsed out of a scene (Show.S01E01.1080p.WEB.H264-GROUP) or
+// fansub ([Group] Show - 05 [1080p]) release title
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Release {
+    // vertical resolution, eg. 1080 for 1080p
+    pub resolution: Option<u32>,
+    pub source: Option<Source>,
+    pub video_codec: Option<VideoCodec>,
+    pub audio_codec: Option<AudioCodec>,
+    pub hdr: Vec<Hdr>,
+    pub group: Option<String>,
+    pub season: Option<u32>,
+    // last season of a pack spanning several, eg. 5 for S01-S05
+    pub last_season: Option<u32>,
+    pub episodes: Vec<u32>,
+    // batch releases, complete seasons and season packs
+    pub batch: bool,
+    pub year: Option<u32>,
+    pub languages: Vec<&'static str>,
+}
+
+struct Patterns {
+    resolution: Regex,
+    resolution_4k: Regex,
+    scene_episode: Regex,
+    cross_episode: Regex,
+    season_pack: Regex,
+    season_word: Regex,
+    fansub_episode: Regex,
+    fansub_group: Regex,
+    scene_group: Regex,
+    batch: Regex,
+    year: Regex,
+    sources: Vec<(Regex, Source)>,
+    video_codecs: Vec<(Regex, VideoCodec)>,
+    audio_codecs: Vec<(Regex, AudioCodec)>,
+    hdr: Vec<(Regex, Hdr)>,
+    languages: Vec<(Regex, &'static str)>,
+}
+
+// separators used in release names, "\b" alone treats '_' as part of a word
+const START: &str = r"(?:^|[\s._\-\[\]()])";
+const END: &str = r"(?:$|[\s._\-\[\]()])";
+
+fn pattern(p: &str) -> Regex {
+    RegexBuilder::new(&format!("{}(?:{}){}", START, p, END))
+        .case_insensitive(true)
+        .build()
+        .unwrap()
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+
+    PATTERNS.get_or_init(|| Patterns {
+        resolution: pattern(r"(\d{3,4})[pi]"),
+        resolution_4k: pattern(r"4k|uhd"),
+        scene_episode: pattern(r"s(\d{1,2})[\s._-]?e(\d{1,4})(?:(?:-?e|-)(\d{1,4}))?"),
+        cross_episode: pattern(r"(\d{1,2})x(\d{2,3})"),
+        season_pack: pattern(r"s(\d{1,2})(?:-s?(\d{1,2}))?"),
+        season_word: pattern(r"season[\s._]?(\d{1,2})"),
+        fansub_episode: Regex::new(
+            r"\s-\s(\d{1,4})(?:v\d)?(?:\s?(?:-|~)\s?(\d{1,4})(?:v\d)?)?(?:$|[\s\[(.])",
+        )
+        .unwrap(),
+        fansub_group: Regex::new(r"^\s*\[([^\]]+)\]").unwrap(),
+        scene_group: Regex::new(r"-([A-Za-z0-9]+)(?:\[[^\]]*\])?$").unwrap(),
+        batch: pattern(r"batch|complete(?:[\s._]series)?|season[\s._]pack"),
+        year: pattern(r"(19\d{2}|20\d{2})"),
+        sources: vec![
+            (pattern(r"(?:hd)?cam(?:rip)?"), Source::Cam),
+            (
+                pattern(r"telesync|(?:hd)?ts|hdtc|telecine"),
+                Source::Telesync,
+            ),
+            (pattern(r"(?:dvd)?scr|screener"), Source::Screener),
+            (pattern(r"web-?rip"), Source::WebRip),
+            (pattern(r"web-?dl|web|amzn|nf|dsnp|hmax"), Source::WebDl),
+            (
+                pattern(r"blu-?ray|bd-?rip|br-?rip|bd-?remux|remux|bd"),
+                Source::BluRay,
+            ),
+            (pattern(r"hdtv|pdtv"), Source::Hdtv),
+            (pattern(r"dvd-?rip|dvd(?:-?r|9|5)?"), Source::Dvd),
+        ],
+        video_codecs: vec![
+            (pattern(r"x\.?265|h\.?265|hevc"), VideoCodec::H265),
+            (pattern(r"x\.?264|h\.?264|avc"), VideoCodec::H264),
+            (pattern(r"av1"), VideoCodec::AV1),
+            (pattern(r"vp9"), VideoCodec::VP9),
+            (pattern(r"xvid|divx"), VideoCodec::XviD),
+        ],
+        audio_codecs: vec![
+            (pattern(r"truehd"), AudioCodec::TrueHD),
+            (pattern(r"dts-?hd(?:[\s.-]?ma)?|dts-?x"), AudioCodec::DTSHD),
+            (pattern(r"dts"), AudioCodec::DTS),
+            (
+                pattern(r"e-?ac-?3|ddp(?:[\s.]?\d\.\d)?|dd\+(?:\d\.\d)?"),
+                AudioCodec::EAC3,
+            ),
+            (pattern(r"ac-?3|dd(?:[\s.]?\d\.\d)?"), AudioCodec::AC3),
+            (pattern(r"flac"), AudioCodec::FLAC),
+            (pattern(r"opus"), AudioCodec::Opus),
+            (pattern(r"aac(?:[\s.]?\d\.\d)?"), AudioCodec::AAC),
+            (pattern(r"mp3"), AudioCodec::MP3),
+        ],
+        hdr: vec![
+            (pattern(r"hdr10(?:\+|plus)"), Hdr::Hdr10Plus),
+            (pattern(r"hdr(?:10)?"), Hdr::Hdr10),
+            (pattern(r"dv|dovi|dolby[\s.]?vision"), Hdr::DolbyVision),
+            (pattern(r"hlg"), Hdr::Hlg),
+        ],
+        languages: vec![
+            (pattern(r"multi(?:[\s.-]?audio)?"), "Multi"),
+            (pattern(r"dual[\s.-]?audio"), "Dual Audio"),
+            (
+                pattern(r"multi[\s.-]?subs?|multiple[\s.]subtitles?"),
+                "Multi Subs",
+            ),
+            (pattern(r"eng(?:lish)?(?:[\s.-]?subs?)?"), "English"),
+            (pattern(r"jap(?:anese)?|jpn"), "Japanese"),
+            (pattern(r"french|vostfr|vff?|truefrench"), "French"),
+            (pattern(r"german|ger"), "German"),
+            (pattern(r"spanish|spa|latino|castellano"), "Spanish"),
+            (pattern(r"italian|ita"), "Italian"),
+            (pattern(r"russian|rus"), "Russian"),
+            (pattern(r"hindi"), "Hindi"),
+            (pattern(r"korean|kor"), "Korean"),
+            (pattern(r"chinese|chs|cht"), "Chinese"),
+        ],
+    })
+}
+
+// tokens that follow a '-' in release names without being a release group
+const NOT_GROUPS: [&str; 6] = ["DL", "RIP", "HD", "MA", "X", "AUDIO"];
+
+impl Release {
+    pub fn parse(title: &str) -> Release {
+        let p = patterns();
+        let title = strip_extension(title.trim());
+
+        let mut release = Release {
+            resolution: parse_resolution(p, title),
+            source: first_match(&p.sources, title),
+            video_codec: first_match(&p.video_codecs, title),
+            audio_codec: first_match(&p.audio_codecs, title),
+            hdr: all_matches(&p.hdr, title),
+            group: parse_group(p, title),
+            languages: all_matches(&p.languages, title),
+            batch: p.batch.is_match(title),
+            ..Default::default()
+        };
+
+        release.year = parse_year(p, title);
+        parse_episodes(p, title, &mut release);
+
+        release
+    }
+
+    // short description of the release's quality for the torrent table, eg. "1080p WEB-DL x265"
+    pub fn summary(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+
+        if let Some(resolution) = self.resolution {
+            parts.push(format!("{}p", resolution));
+        }
+        if let Some(source) = self.source {
+            parts.push(source.to_string());
+        }
+        if let Some(codec) = self.video_codec {
+            parts.push(codec.to_string());
+        }
+        for hdr in &self.hdr {
+            parts.push(hdr.to_string());
+        }
+
+        parts.join(" ")
+    }
+}
+
+fn strip_extension(title: &str) -> &str {
+    for extension in [".mkv", ".mp4", ".avi", ".m4v", ".ts", ".webm"].iter() {
+        if title.len() > extension.len()
+            && title.is_char_boundary(title.len() - extension.len())
+            && title[title.len() - extension.len()..].eq_ignore_ascii_case(extension)
+        {
+            return &title[..title.len() - extension.len()];
+        }
+    }
+
+    title
+}
+
+fn first_match<T: Copy>(patterns: &[(Regex, T)], title: &str) -> Option<T> {
+    patterns
+        .iter()
+        .find(|(regex, _)| regex.is_match(title))
+        .map(|(_, value)| *value)
+}
+
+fn all_matches<T: Copy + PartialEq>(patterns: &[(Regex, T)], title: &str) -> Vec<T> {
+    let mut matches: Vec<T> = Vec::new();
+    for (regex, value) in patterns {
+        if regex.is_match(title) && !matches.contains(value) {
+            matches.push(*value);
+        }
+    }
+
+    matches
+}
+
+fn parse_resolution(p: &Patterns, title: &str) -> Option<u32> {
+    let resolution = p
+        .resolution
+        .captures(title)
+        .and_then(|c| c[1].parse().ok())
+        .filter(|r| [240, 360, 480, 540, 576, 720, 1080, 1440, 2160, 4320].contains(r));
+
+    if resolution.is_none() && p.resolution_4k.is_match(title) {
+        return Some(2160);
+    }
+
+    resolution
+}
+
+// the last year-like number wins, one at the very start is usually part of the
+// name (eg. "1917.2019.1080p", "2001 A Space Odyssey")
+fn parse_year(p: &Patterns, title: &str) -> Option<u32> {
+    let mut year = None;
+    let mut start = 0;
+
+    // separators are shared between neighbouring years, so matches can't be
+    // collected with captures_iter
+    while let Some(m) = p.year.captures(&title[start..]).and_then(|c| c.get(1)) {
+        if start + m.start() > 0 {
+            year = m.as_str().parse().ok();
+        }
+        start += m.end();
+    }
+
+    year
+}
+
+fn parse_group(p: &Patterns, title: &str) -> Option<String> {
+    if let Some(c) = p.fansub_group.captures(title) {
+        return Some(String::from(c[1].trim()));
+    }
+
+    let group = p.scene_group.captures(title)?[1].to_string();
+    if NOT_GROUPS.contains(&group.to_uppercase().as_str()) {
+        return None;
+    }
+
+    Some(group)
+}
+
+fn parse_episodes(p: &Patterns, title: &str, release: &mut Release) {
+    if let Some(c) = p.scene_episode.captures(title) {
+        release.season = c[1].parse().ok();
+        release.episodes = episode_range(&c[2], c.get(3).map(|m| m.as_str()));
+        return;
+    }
+
+    if let Some(c) = p.cross_episode.captures(title) {
+        release.season = c[1].parse().ok();
+        release.episodes = episode_range(&c[2], None);
+        return;
+    }
+
+    if let Some(c) = p.season_pack.captures(title) {
+        release.season = c[1].parse().ok();
+        release.last_season = c
+            .get(2)
+            .and_then(|m| m.as_str().parse().ok())
+            .filter(|&last| release.season.is_some_and(|first| last > first));
+    } else if let Some(c) = p.season_word.captures(title) {
+        release.season = c[1].parse().ok();
+    }
+
+    // "Movie - 2019 [1080p]" is a year, not episode 2019
+    let is_year = |c: &Captures| c[1].parse().ok() == release.year;
+    if let Some(c) = p.fansub_episode.captures(title).filter(|c| !is_year(c)) {
+        release.episodes = episode_range(&c[1], c.get(2).map(|m| m.as_str()));
+        if release.episodes.len() > 1 {
+            release.batch = true;
+        }
+    }
+
+    // a season without an episode is the whole season, eg. "Show S2 - 05" is a single episode
+    if release.season.is_some() && release.episodes.is_empty() {
+        release.batch = true;
+    }
+}
+
+fn episode_range(first: &str, last: Option<&str>) -> Vec<u32> {
+    let first: u32 = match first.parse() {
+        Ok(n) => n,
+        Err(_) => return Vec::new(),
+    };
+
+    match last.and_then(|l| l.parse::<u32>().ok()) {
+        Some(last) if last > first && last - first < 2000 => (first..=last).collect(),
+        _ => vec![first],
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Source::Cam => "CAM",
+            Source::Telesync => "TS",
+            Source::Screener => "SCR",
+            Source::Dvd => "DVD",
+            Source::Hdtv => "HDTV",
+            Source::WebRip => "WEBRip",
+            Source::WebDl => "WEB-DL",
+            Source::BluRay => "BluRay",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Source {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        first_match(&patterns().sources, s.trim())
+            .ok_or_else(|| format!("Unknown release source '{}'", s.trim()))
+    }
+}
+
+impl fmt::Display for VideoCodec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            VideoCodec::H264 => "x264",
+            VideoCodec::H265 => "x265",
+            VideoCodec::AV1 => "AV1",
+            VideoCodec::VP9 => "VP9",
+            VideoCodec::XviD => "XviD",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for VideoCodec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        first_match(&patterns().video_codecs, s.trim())
+            .ok_or_else(|| format!("Unknown video codec '{}'", s.trim()))
+    }
+}
+
+impl fmt::Display for AudioCodec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            AudioCodec::TrueHD => "TrueHD",
+            AudioCodec::DTSHD => "DTS-HD",
+            AudioCodec::DTS => "DTS",
+            AudioCodec::EAC3 => "EAC3",
+            AudioCodec::AC3 => "AC3",
+            AudioCodec::FLAC => "FLAC",
+            AudioCodec::Opus => "Opus",
+            AudioCodec::AAC => "AAC",
+            AudioCodec::MP3 => "MP3",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Hdr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Hdr::Hdr10 => "HDR",
+            Hdr::Hdr10Plus => "HDR10+",
+            Hdr::DolbyVision => "DV",
+            Hdr::Hlg => "HLG",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// enums are written out the way they're displayed, eg. "WEB-DL" rather than "WebDl"
+macro_rules! serialize_as_display {
+    ($($name:ty),*) => {
+        $(impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        })*
+    };
+}
+
+serialize_as_display!(Source, VideoCodec, AudioCodec, Hdr);
+
+// parses "1080p", "1080" or "4k" into a vertical resolution
+pub fn parse_resolution_arg(s: &str) -> Result<u32, String> {
+    let s = s.trim().to_lowercase();
+    if s == "4k" || s == "uhd" {
+        return Ok(2160);
+    }
+
+    s.trim_end_matches(['p', 'i'])
+        .parse()
+        .map_err(|_| format!("Unknown resolution '{}', expected eg. 720p or 1080p", s))
+}
+
+// parses a comma separated list of sources, eg. "cam,ts,scr"
+pub fn parse_sources(s: &str) -> Result<Vec<Source>, String> {
+    s.split(',')
+        .filter(|source| !source.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
@@ -0,0 +1,138 @@
+// parts of a relevance score, adding up to 100
+const COVERAGE_POINTS: f64 = 70.0;
+const ORDER_POINTS: f64 = 15.0;
+const PHRASE_POINTS: f64 = 15.0;
+
+// words less similar than this are treated as different words
+const FUZZY_THRESHOLD: f64 = 0.75;
+// partial matches count for less than an exact word
+const PREFIX_MATCH: f64 = 0.8;
+const FUZZY_MATCH: f64 = 0.9;
+
+// scores how well a title matches the search query from 0 to 100: how many of the query's
+// words are found (allowing typos and partial words), whether they appear in the same
+// order, and whether the whole query appears as an exact phrase
+pub fn relevance(query: &str, title: &str) -> u32 {
+    let query = tokens(query);
+    let title = tokens(title);
+
+    if query.is_empty() {
+        return 100;
+    }
+    if title.is_empty() {
+        return 0;
+    }
+
+    let matches: Vec<Option<(usize, f64)>> =
+        query.iter().map(|word| best_match(word, &title)).collect();
+
+    let coverage = matches
+        .iter()
+        .map(|m| m.map_or(0.0, |(_, similarity)| similarity))
+        .sum::<f64>()
+        / query.len() as f64;
+
+    let positions: Vec<usize> = matches.iter().filter_map(|m| m.map(|(i, _)| i)).collect();
+    let order = if positions.is_empty() {
+        0.0
+    } else if query.len() == 1 {
+        1.0
+    } else {
+        positions.windows(2).filter(|w| w[0] < w[1]).count() as f64 / (query.len() - 1) as f64
+    };
+
+    let phrase = if title.windows(query.len()).any(|w| w == query.as_slice()) {
+        1.0
+    } else {
+        0.0
+    };
+
+    (COVERAGE_POINTS * coverage + ORDER_POINTS * order + PHRASE_POINTS * phrase).round() as u32
+}
+
+pub fn parse_relevance(s: &str) -> Result<u32, String> {
+    match s.trim().trim_end_matches('%').parse::<u32>() {
+        Ok(n) if n <= 100 => Ok(n),
+        _ => Err(format!(
+            "relevance must be a number from 0 to 100, got '{}'",
+            s
+        )),
+    }
+}
+
+// lowercased words, numbers lose their leading zeros so "5" matches "05"
+fn tokens(s: &str) -> Vec<String> {
+    s.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            if word.chars().all(|c| c.is_ascii_digit()) {
+                let trimmed = word.trim_start_matches('0');
+                String::from(if trimmed.is_empty() { "0" } else { trimmed })
+            } else {
+                word.to_lowercase()
+            }
+        })
+        .collect()
+}
+
+// position and similarity of the title word closest to a query word
+fn best_match(word: &str, title: &[String]) -> Option<(usize, f64)> {
+    let mut best: Option<(usize, f64)> = None;
+
+    for (i, candidate) in title.iter().enumerate() {
+        let similarity = similarity(word, candidate);
+        if similarity > best.map_or(0.0, |(_, s)| s) {
+            best = Some((i, similarity));
+        }
+        if similarity >= 1.0 {
+            break;
+        }
+    }
+
+    best
+}
+
+fn similarity(word: &str, candidate: &str) -> f64 {
+    if word == candidate {
+        return 1.0;
+    }
+
+    // "s05" matches "s05e10", but "202" doesn't match "2021"
+    if word.chars().count() >= 3 && candidate.starts_with(word) {
+        let splits_number = word.ends_with(|c: char| c.is_ascii_digit())
+            && candidate[word.len()..].starts_with(|c: char| c.is_ascii_digit());
+        if !splits_number {
+            return PREFIX_MATCH;
+        }
+    }
+
+    // numbers (episodes, years, resolutions) aren't fuzzy matched
+    if word.chars().any(|c| c.is_ascii_digit()) || candidate.chars().any(|c| c.is_ascii_digit()) {
+        return 0.0;
+    }
+
+    let longest = word.chars().count().max(candidate.chars().count());
+    let similarity = 1.0 - levenshtein(word, candidate) as f64 / longest as f64;
+    if similarity >= FUZZY_THRESHOLD {
+        similarity * FUZZY_MATCH
+    } else {
+        0.0
+    }
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + if a_char == *b_char { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
@@ -0,0 +1,414 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::error::Error;
+use crate::http::Client;
+use crate::limits::{Pool, RateLimiter};
+use crate::provider::{self, Fetched};
+use crate::{Torrent, UserParameters};
+
+// one fetched page of results, sent back by the fetching threads
+pub struct Page {
+    pub provider: &'static str,
+    pub number: u32,
+    pub result: Result<Fetched, Error>,
+    pub latency: Duration,
+    // no more pages follow for this query
+    pub last: bool,
+}
+
+// where a provider's queries send their pages, paging stops once the search gave up on it
+#[derive(Clone)]
+pub struct Pages {
+    tx: Sender<Page>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Pages {
+    pub fn new(tx: Sender<Page>, cancelled: Arc<AtomicBool>) -> Pages {
+        Pages { tx, cancelled }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    // false once the search isn't waiting for more pages
+    pub fn send(&self, page: Page) -> bool {
+        !self.is_cancelled() && self.tx.send(page).is_ok()
+    }
+}
+
+// how a provider's part of the search went, shown after the search
+#[derive(Clone, Default, Debug)]
+pub struct Stats {
+    pub provider: &'static str,
+    pub pages: usize,
+    pub failed_pages: usize,
+    // pages read from the cache rather than the site
+    pub cached_pages: usize,
+    pub results: usize,
+    // rows that were found but couldn't be read
+    pub skipped: usize,
+    // of the last page fetched
+    pub status: Option<u16>,
+    // total time spent on pages, successful or not
+    pub latency: Duration,
+    pub mirrors: Vec<String>,
+    pub errors: Vec<String>,
+    pub timed_out: bool,
+    // one line per page and mirror failover, shown with --verbose
+    pub log: Vec<String>,
+}
+
+struct Progress {
+    stats: Stats,
+    queries_left: usize,
+    // set once the provider timed out or the search is over, its queries stop paging
+    cancelled: Arc<AtomicBool>,
+}
+
+// results of a search that is still running, collected as the pages come in
+pub struct Search {
+    rx: Receiver<Page>,
+    progress: Vec<Progress>,
+    torrents: Vec<Torrent>,
+    // providers still loading past this point are left out of the results
+    deadline: Option<Instant>,
+}
+
+impl Stats {
+    // eg. "nyaa: 2 pages, 150 results, HTTP 200, 340 ms per page, from https://nyaa.si"
+    pub fn summary(&self) -> String {
+        let mut parts = vec![
+            format!("{} {}", self.pages, plural(self.pages, "page", "pages")),
+            format!(
+                "{} {}",
+                self.results,
+                plural(self.results, "result", "results")
+            ),
+        ];
+
+        if self.cached_pages > 0 {
+            parts.push(format!("{} cached", self.cached_pages));
+        }
+        if self.skipped > 0 {
+            parts.push(
+                Error::PartialRows {
+                    skipped: self.skipped,
+                    rows: self.results + self.skipped,
+                }
+                .to_string(),
+            );
+        }
+        if let Some(status) = self.status {
+            parts.push(format!("HTTP {}", status));
+        }
+
+        let fetched = self.pages + self.failed_pages;
+        if fetched > 0 {
+            parts.push(format!(
+                "{} ms per page",
+                self.latency.as_millis() / fetched as u128
+            ));
+        }
+        if !self.mirrors.is_empty() {
+            parts.push(format!("from {}", self.mirrors.join(", ")));
+        }
+        if let Some(err) = self.errors.first() {
+            parts.push(format!(
+                "{} {} failed: {}",
+                self.failed_pages,
+                plural(self.failed_pages, "page", "pages"),
+                err
+            ));
+        }
+        if self.timed_out {
+            parts.push(String::from("timed out"));
+        }
+
+        format!("{}: {}", self.provider, parts.join(", "))
+    }
+}
+
+// a number of pages, or "all" for every page a site has
+pub fn parse_depth(s: &str) -> Result<Option<u32>, String> {
+    if s.trim().eq_ignore_ascii_case("all") {
+        return Ok(None);
+    }
+
+    match s.trim().parse() {
+        Ok(0) | Err(_) => Err(String::from("Depth must be a number of pages or 'all'")),
+        Ok(depth) => Ok(Some(depth)),
+    }
+}
+
+fn plural<'a>(count: usize, one: &'a str, many: &'a str) -> &'a str {
+    if count == 1 {
+        one
+    } else {
+        many
+    }
+}
+
+impl Search {
+    // sends every provider's requests off in the background
+    pub fn start(user_parameters: &UserParameters) -> Search {
+        let (tx, rx) = mpsc::channel();
+        let mut providers = Vec::new();
+
+        // the workers exit once the last queued page is done
+        let pool = Pool::new(user_parameters.limits.concurrency);
+        let limiter = Arc::new(RateLimiter::new(&user_parameters.limits));
+
+        for provider in &user_parameters.providers {
+            let mut client = Client::new(
+                user_parameters.http.for_provider(provider.name()),
+                user_parameters.proxy.as_ref(),
+            )
+            .with_rate_limit(Arc::clone(&limiter));
+            if let Some(cache) = &user_parameters.cache {
+                client = client.with_cache(Arc::clone(cache));
+            }
+            let client = Arc::new(client);
+            let mirrors = provider::mirrors(provider, &user_parameters.mirrors);
+
+            let queries: Vec<Arc<String>> = match (
+                &user_parameters.episodes,
+                provider.capabilities().episode_naming,
+            ) {
+                (Some(episodes), Some(naming)) => episodes
+                    .queries(&user_parameters.search_query, naming)
+                    .into_iter()
+                    .map(Arc::new)
+                    .collect(),
+                _ => vec![Arc::clone(&user_parameters.search_query)],
+            };
+
+            providers.push((provider, client, mirrors, queries));
+        }
+
+        let queries_per_provider: Vec<(&'static str, usize)> = providers
+            .iter()
+            .map(|(provider, _, _, queries)| (provider.name(), queries.len()))
+            .collect();
+        let search = Search::new(rx, &queries_per_provider, user_parameters.timeout);
+
+        for (provider, client, mirrors, queries) in &providers {
+            let pages = Pages::new(tx.clone(), search.cancelled(provider.name()));
+            for query in queries {
+                provider::query(
+                    &pool,
+                    provider,
+                    client,
+                    mirrors,
+                    pages.clone(),
+                    query,
+                    user_parameters.search_depth,
+                );
+            }
+        }
+
+        search
+    }
+
+    // queries lists how many queries each provider pages through
+    pub fn new(
+        rx: Receiver<Page>,
+        queries: &[(&'static str, usize)],
+        timeout: Option<Duration>,
+    ) -> Search {
+        Search {
+            rx,
+            progress: queries
+                .iter()
+                .map(|&(provider, queries_left)| Progress {
+                    stats: Stats {
+                        provider,
+                        ..Default::default()
+                    },
+                    queries_left,
+                    cancelled: Arc::new(AtomicBool::new(false)),
+                })
+                .collect(),
+            torrents: Vec::new(),
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+        }
+    }
+
+    // waits up to `wait` for the next page, true when results or loading providers changed
+    pub fn poll(&mut self, wait: Duration) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+
+        let wait = match self.deadline {
+            Some(deadline) => wait.min(deadline.saturating_duration_since(Instant::now())),
+            None => wait,
+        };
+
+        match self.rx.recv_timeout(wait) {
+            Ok(page) => self.add_page(page),
+            Err(RecvTimeoutError::Timeout) => {
+                if self.deadline.is_some_and(|d| Instant::now() >= d) {
+                    for progress in self.progress.iter_mut().filter(|p| p.queries_left > 0) {
+                        progress.stats.timed_out = true;
+                        progress.cancelled.store(true, Ordering::Relaxed);
+                    }
+                    return true;
+                }
+                false
+            }
+            // every thread is done, pages can't go missing so this only happens once all arrived
+            Err(RecvTimeoutError::Disconnected) => {
+                for progress in &mut self.progress {
+                    progress.queries_left = 0;
+                }
+                true
+            }
+        }
+    }
+
+    // blocks until every provider finished or timed out
+    pub fn wait(&mut self) {
+        while !self.is_finished() {
+            self.poll(Duration::from_secs(1));
+        }
+    }
+
+    fn add_page(&mut self, page: Page) -> bool {
+        let progress = match self
+            .progress
+            .iter_mut()
+            .find(|p| p.stats.provider == page.provider)
+        {
+            Some(progress) if !progress.stats.timed_out => progress,
+            _ => return false,
+        };
+        if page.last {
+            progress.queries_left = progress.queries_left.saturating_sub(1);
+        }
+
+        let stats = &mut progress.stats;
+        stats.latency += page.latency;
+
+        match page.result {
+            Ok(fetched) => {
+                for failover in &fetched.failovers {
+                    stats.log.push(format!(
+                        "{} page {}: skipped {}",
+                        page.provider, page.number, failover
+                    ));
+                }
+                stats.log.push(format!(
+                    "{} page {}: {} results, {} rows skipped, HTTP {} from {}{} in {} ms{}",
+                    page.provider,
+                    page.number,
+                    fetched.page.torrents.len(),
+                    fetched.page.skipped,
+                    fetched.status,
+                    fetched.mirror,
+                    if fetched.cached { " (cached)" } else { "" },
+                    page.latency.as_millis(),
+                    if page.last { ", last page" } else { "" }
+                ));
+
+                stats.pages += 1;
+                if fetched.cached {
+                    stats.cached_pages += 1;
+                }
+                stats.results += fetched.page.torrents.len();
+                stats.skipped += fetched.page.skipped;
+                stats.status = Some(fetched.status);
+                if !stats.mirrors.contains(&fetched.mirror) {
+                    stats.mirrors.push(fetched.mirror);
+                }
+                self.torrents.extend(fetched.page.torrents);
+            }
+            Err(err) => {
+                stats.log.push(format!(
+                    "{} page {}: failed after {} ms: {}",
+                    page.provider,
+                    page.number,
+                    page.latency.as_millis(),
+                    err
+                ));
+
+                stats.failed_pages += 1;
+                if let Some(status) = err.status() {
+                    stats.status = Some(status);
+                }
+                stats.errors.push(err.to_string());
+            }
+        }
+
+        true
+    }
+
+    // the flag provider's queries check before fetching each page
+    pub fn cancelled(&self, provider: &str) -> Arc<AtomicBool> {
+        self.progress
+            .iter()
+            .find(|p| p.stats.provider == provider)
+            .map(|p| Arc::clone(&p.cancelled))
+            .unwrap_or_default()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.progress
+            .iter()
+            .all(|p| p.queries_left == 0 || p.stats.timed_out)
+    }
+
+    pub fn loading(&self) -> Vec<&'static str> {
+        self.progress
+            .iter()
+            .filter(|p| p.queries_left > 0 && !p.stats.timed_out)
+            .map(|p| p.stats.provider)
+            .collect()
+    }
+
+    pub fn timed_out(&self) -> Vec<&'static str> {
+        self.progress
+            .iter()
+            .filter(|p| p.stats.timed_out)
+            .map(|p| p.stats.provider)
+            .collect()
+    }
+
+    pub fn stats(&self) -> Vec<&Stats> {
+        self.progress.iter().map(|p| &p.stats).collect()
+    }
+
+    // one line per provider, followed by every page's details when verbose
+    pub fn summary(&self, verbose: bool) -> Vec<String> {
+        let mut lines: Vec<String> = self.progress.iter().map(|p| p.stats.summary()).collect();
+        if verbose {
+            for progress in &self.progress {
+                lines.extend(progress.stats.log.iter().cloned());
+            }
+        }
+
+        lines
+    }
+
+    // results received so far, unprocessed
+    pub fn torrents(&self) -> &[Torrent] {
+        &self.torrents
+    }
+
+    pub fn into_torrents(mut self) -> Vec<Torrent> {
+        std::mem::take(&mut self.torrents)
+    }
+}
+
+// pages still queued when a selection is made aren't fetched
+impl Drop for Search {
+    fn drop(&mut self) {
+        for progress in &self.progress {
+            progress.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+}
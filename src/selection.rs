@@ -0,0 +1,124 @@
+use crate::sort;
+use crate::SortKey;
+
+// what the user typed at the result table prompt, torrent positions are 0 based
+#[derive(Clone, PartialEq, Debug)]
+pub enum Command {
+    Next,
+    Previous,
+    Quit,
+    Sort(Vec<SortKey>),
+    // an empty filter shows every torrent again
+    Filter(String),
+    Inspect(usize),
+    Select(Vec<usize>),
+}
+
+// parses a prompt line for a table of count torrents
+pub fn parse_command(input: &str, count: usize) -> Result<Command, String> {
+    let input = input.trim();
+    let (name, rest) = match input.split_once(char::is_whitespace) {
+        Some((name, rest)) => (name, rest.trim()),
+        None => (input, ""),
+    };
+
+    match name.to_lowercase().as_str() {
+        "" => Err(String::from("Select torrent(s) by the # on the left")),
+        "n" if rest.is_empty() => Ok(Command::Next),
+        "p" if rest.is_empty() => Ok(Command::Previous),
+        "q" if rest.is_empty() => Ok(Command::Quit),
+        "s" if rest.is_empty() => Err(String::from(
+            "Give sort keys, eg. 's size' or 's seeds:asc'",
+        )),
+        "s" => Ok(Command::Sort(sort::parse_sort_keys(rest)?)),
+        "f" => Ok(Command::Filter(String::from(rest))),
+        "i" => {
+            let selection = parse_selection(rest, count)?;
+            match selection[..] {
+                [n] => Ok(Command::Inspect(n)),
+                _ => Err(String::from("Inspect one torrent at a time, eg. 'i 3'")),
+            }
+        }
+        _ => Ok(Command::Select(parse_selection(input, count)?)),
+    }
+}
+
+// parses numbers from the table's # column, eg. "1 3-5,8", "all" or "^2" for everything but 2.
+// exclusions on their own start from every torrent, the result is sorted and free of duplicates
+pub fn parse_selection(input: &str, count: usize) -> Result<Vec<usize>, String> {
+    if count == 0 {
+        return Err(String::from("No torrents to select"));
+    }
+
+    let mut included = vec![false; count];
+    let mut excluded = vec![false; count];
+    let mut any_included = false;
+
+    let terms = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|term| !term.is_empty());
+
+    for term in terms {
+        if term.eq_ignore_ascii_case("all") {
+            included.iter_mut().for_each(|i| *i = true);
+            any_included = true;
+        } else if let Some(term) = term.strip_prefix('^') {
+            for n in parse_term(term, count)? {
+                excluded[n] = true;
+            }
+        } else {
+            for n in parse_term(term, count)? {
+                included[n] = true;
+            }
+            any_included = true;
+        }
+    }
+
+    if !any_included && !excluded.contains(&true) {
+        return Err(String::from("No torrents selected"));
+    }
+
+    let selection: Vec<usize> = (0..count)
+        .filter(|&n| (!any_included || included[n]) && !excluded[n])
+        .collect();
+
+    if selection.is_empty() {
+        return Err(String::from("No torrents selected"));
+    }
+
+    Ok(selection)
+}
+
+// a single number or an inclusive range such as "3-7"
+fn parse_term(term: &str, count: usize) -> Result<std::ops::Range<usize>, String> {
+    let number = |s: &str| -> Result<usize, String> {
+        let n: usize = s.trim().parse().map_err(|_| {
+            format!(
+                "'{}' isn't a number, select torrents like '1 3-5,8', 'all' or '^2'",
+                term
+            )
+        })?;
+
+        if n == 0 || n > count {
+            return Err(format!("{} is out of range, pick from 1 to {}", n, count));
+        }
+        Ok(n)
+    };
+
+    let (start, end) = match term.split_once('-') {
+        Some((start, end)) => (number(start)?, number(end)?),
+        None => {
+            let n = number(term)?;
+            (n, n)
+        }
+    };
+
+    if start > end {
+        return Err(format!(
+            "Range {} is backwards, write it as {}-{}",
+            term, end, start
+        ));
+    }
+
+    Ok(start - 1..end)
+}
@@ -1,15 +1,32 @@
+use std::collections::HashMap;
 use std::env;
+use std::fmt::Display;
 use std::fs;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::rc::Rc;
+use std::time::Duration;
 
 use config::{Config, ConfigError, File};
 use directories::{ProjectDirs, UserDirs};
 
+use crate::filter::{self, Filter};
+use crate::http::{HttpConfig, HttpSettings};
+use crate::limits::Limits;
+use crate::profile::{Profile, Profiles};
+use crate::provider;
+use crate::release;
+use crate::relevance;
+use crate::trackers::{self, TrackerList, TrackerMode, Trackers};
 use crate::Settings;
 
+// seconds a search waits for each site
+const DEFAULT_TIMEOUT: u64 = 30;
+
+// seconds a fetched page is reused for
+const DEFAULT_CACHE_TTL: u64 = 900;
+
 impl Default for Settings {
     fn default() -> Self {
         if let Some(user_dirs) = UserDirs::new() {
@@ -25,7 +42,16 @@ impl Default for Settings {
                 default_directory: Rc::clone(&downloads_dir),
                 default_proxy: String::from(""),
                 autodownload: false,
+                tui: false,
+                timeout: DEFAULT_TIMEOUT,
                 torrent_client: String::from(""),
+                mirrors: HashMap::new(),
+                http: HttpConfig::default(),
+                limits: Limits::default(),
+                cache_ttl: DEFAULT_CACHE_TTL,
+                trackers: Trackers::default(),
+                filter: Filter::default(),
+                profiles: Profiles::default(),
             }
         } else {
             eprintln!("Error getting home directory");
@@ -122,6 +148,9 @@ impl Settings {
         let movie_dir = Settings::validate_path(s.get::<String>("movie_dir"), &mut fallback_dir);
 
         let autodownload = s.get_bool("autodownload").unwrap_or(false);
+        let tui = s.get_bool("tui").unwrap_or(false);
+        let timeout =
+            Settings::parsed_setting(&s, "timeout", str::parse).unwrap_or(DEFAULT_TIMEOUT);
 
         let torrent_client = s
             .get::<String>("torrent_client")
@@ -131,6 +160,79 @@ impl Settings {
             .get::<String>("default_proxy")
             .unwrap_or_else(|_| String::from(""));
 
+        let mirrors = s
+            .get::<HashMap<String, Vec<String>>>("mirrors")
+            .unwrap_or_default();
+
+        let global = Settings::http_settings(&s, "http", &HttpSettings::default());
+        let mut http = HttpConfig {
+            providers: HashMap::new(),
+            global: global.clone(),
+        };
+        for name in provider::names() {
+            let settings = Settings::http_settings(&s, &format!("http.{}", name), &global);
+            if settings != global {
+                http.providers.insert(String::from(name), settings);
+            }
+        }
+
+        let defaults = Limits::default();
+        let limits = Limits {
+            concurrency: Settings::parsed_setting(&s, "limits.concurrency", str::parse)
+                .unwrap_or(defaults.concurrency),
+            requests_per_second: Settings::parsed_setting(
+                &s,
+                "limits.requests_per_second",
+                str::parse,
+            )
+            .unwrap_or(defaults.requests_per_second),
+            burst: Settings::parsed_setting(&s, "limits.burst", str::parse)
+                .unwrap_or(defaults.burst),
+        };
+
+        let cache_ttl =
+            Settings::parsed_setting(&s, "cache.ttl", str::parse).unwrap_or(DEFAULT_CACHE_TTL);
+
+        let mut trackers = Trackers {
+            global: Settings::tracker_list(&s, "trackers").unwrap_or_default(),
+            providers: HashMap::new(),
+        };
+        for name in provider::names() {
+            if let Some(list) = Settings::tracker_list(&s, &format!("trackers.{}", name)) {
+                trackers.providers.insert(String::from(name), list);
+            }
+        }
+
+        let filter = Filter {
+            min_size: Settings::parsed_setting(&s, "filters.min_size", str::parse),
+            max_size: Settings::parsed_setting(&s, "filters.max_size", str::parse),
+            min_seeders: Settings::parsed_setting(&s, "filters.min_seeders", str::parse),
+            include: Settings::parsed_setting(&s, "filters.include", filter::build_regex),
+            exclude: Settings::parsed_setting(&s, "filters.exclude", filter::build_regex),
+            min_resolution: Settings::parsed_setting(
+                &s,
+                "filters.min_resolution",
+                release::parse_resolution_arg,
+            ),
+            exclude_sources: Settings::parsed_setting(
+                &s,
+                "filters.exclude_sources",
+                release::parse_sources,
+            )
+            .unwrap_or_default(),
+            min_relevance: Settings::parsed_setting(
+                &s,
+                "filters.min_relevance",
+                relevance::parse_relevance,
+            ),
+        };
+
+        let profiles = Profiles {
+            anime: Settings::profile(&s, "profiles.anime"),
+            movie: Settings::profile(&s, "profiles.movie"),
+            tvshow: Settings::profile(&s, "profiles.tvshow"),
+        };
+
         Ok(Settings {
             anime_dir,
             tvshow_dir,
@@ -138,7 +240,132 @@ impl Settings {
             default_directory: fallback_dir.value(),
             default_proxy,
             autodownload,
+            tui,
+            timeout,
             torrent_client,
+            mirrors,
+            http,
+            limits,
+            cache_ttl,
+            trackers,
+            filter,
+            profiles,
+        })
+    }
+
+    // empty values count as unset, invalid ones are reported and ignored
+    fn parsed_setting<T, E: Display>(
+        s: &Config,
+        key: &str,
+        parse: impl Fn(&str) -> Result<T, E>,
+    ) -> Option<T> {
+        let value = s.get::<String>(key).ok()?;
+        if value.trim().is_empty() {
+            return None;
+        }
+
+        match parse(value.trim()) {
+            Ok(v) => Some(v),
+            Err(err) => {
+                eprintln!("Invalid {} in Settings.toml: {}", key, err);
+                None
+            }
+        }
+    }
+
+    // like parsed_setting for every entry of a list, invalid entries are left out
+    fn parsed_list<T, E: Display>(
+        s: &Config,
+        key: &str,
+        parse: impl Fn(&str) -> Result<T, E>,
+    ) -> Vec<T> {
+        s.get::<Vec<String>>(key)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|value| match parse(value.trim()) {
+                Ok(v) => Some(v),
+                Err(err) => {
+                    eprintln!("Invalid {} in Settings.toml: {}", key, err);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn profile(s: &Config, key: &str) -> Profile {
+        let list = |name: &str| {
+            s.get::<Vec<String>>(&format!("{}.{}", key, name))
+                .unwrap_or_default()
+        };
+
+        Profile {
+            resolutions: Settings::parsed_list(
+                s,
+                &format!("{}.resolutions", key),
+                release::parse_resolution_arg,
+            ),
+            codecs: Settings::parsed_list(s, &format!("{}.codecs", key), str::parse),
+            groups: list("groups"),
+            forbidden_words: list("forbidden_words"),
+            forbidden_sources: Settings::parsed_list(
+                s,
+                &format!("{}.forbidden_sources", key),
+                str::parse,
+            ),
+            min_size: Settings::parsed_setting(s, &format!("{}.min_size", key), str::parse),
+            max_size: Settings::parsed_setting(s, &format!("{}.max_size", key), str::parse),
+        }
+    }
+
+    // unset values fall back to the given defaults, timeouts are in seconds and backoff in ms
+    fn http_settings(s: &Config, key: &str, defaults: &HttpSettings) -> HttpSettings {
+        let setting = |name: &str| {
+            Settings::parsed_setting(s, &format!("{}.{}", key, name), str::parse::<u64>)
+        };
+
+        HttpSettings {
+            connect_timeout: setting("connect_timeout")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.connect_timeout),
+            read_timeout: setting("read_timeout")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.read_timeout),
+            retries: Settings::parsed_setting(s, &format!("{}.retries", key), str::parse)
+                .unwrap_or(defaults.retries),
+            backoff: setting("backoff")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.backoff),
+        }
+    }
+
+    fn tracker_list(s: &Config, key: &str) -> Option<TrackerList> {
+        let mode = s.get::<String>(&format!("{}.mode", key)).ok();
+        let list = s.get::<Vec<String>>(&format!("{}.list", key)).ok();
+        let file = s.get::<String>(&format!("{}.file", key)).ok();
+
+        if mode.is_none() && list.is_none() && file.is_none() {
+            return None;
+        }
+
+        let mode = match mode {
+            Some(mode) => TrackerMode::new(&mode).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                TrackerMode::Extend
+            }),
+            None => TrackerMode::Extend,
+        };
+
+        let mut list = list.unwrap_or_default();
+        if let Some(file) = file.filter(|f| !f.is_empty()) {
+            match trackers::read_tracker_file(Path::new(&file)) {
+                Ok(file_trackers) => list.extend(file_trackers),
+                Err(err) => eprintln!("Error reading tracker file {}: {}", file, err),
+            }
+        }
+
+        Some(TrackerList {
+            mode,
+            trackers: list,
         })
     }
 
@@ -190,9 +417,119 @@ torrent_client = \"\"
 # uses the torrent-client chosen to begin downloading the torrent
 autodownload = false
 
+# browse results in a full screen view instead of the printed table, like --tui
+# (only available when magnetfinder is built with the tui feature)
+tui = false
+
+# seconds to wait for a website before showing results without it, like --timeout
+# (0 waits as long as it takes)
+timeout = 30
+
 # setting a default proxy allows you to tunnel all scraping from torrent websites through
 # this set proxy by default. If using a socks5 proxy, format ip like so: socks5://192.168.1.1:9000
-default_proxy = \"\"",
+default_proxy = \"\"
+
+#[ Filters ]
+# results not matching these filters are dropped before being displayed, can be
+# overridden with --min-size, --max-size, --min-seeders, --include, --exclude,
+# --min-resolution, --exclude-source and --min-relevance
+# sizes accept units like \"700 MB\" or \"4 GiB\", include/exclude are case insensitive regexes
+# min_resolution (ex: \"720p\") and exclude_sources (ex: \"cam,ts,scr\") use the quality read from titles
+[filters]
+min_size = \"\"
+max_size = \"\"
+min_seeders = 0
+include = \"\"
+exclude = \"\"
+min_resolution = \"\"
+exclude_sources = \"\"
+# how closely titles must match the search query, from 0 to 100
+min_relevance = 0
+
+#[ Profiles ]
+# quality profiles for each type of media, results are scored against the profile of the
+# searched media and listed best score first (--media picks the profile on the command line)
+# resolutions, codecs and groups are listed most preferred first
+# (ex: resolutions = [\"1080p\", \"720p\"], codecs = [\"x265\", \"x264\"], groups = [\"SubsPlease\"])
+# results containing a forbidden word or source (ex: forbidden_sources = [\"cam\", \"ts\"]),
+# or falling outside the size limits are dropped
+[profiles.anime]
+resolutions = []
+codecs = []
+groups = []
+forbidden_words = []
+forbidden_sources = []
+min_size = \"\"
+max_size = \"\"
+
+[profiles.movie]
+resolutions = []
+codecs = []
+groups = []
+forbidden_words = []
+forbidden_sources = []
+min_size = \"\"
+max_size = \"\"
+
+[profiles.tvshow]
+resolutions = []
+codecs = []
+groups = []
+forbidden_words = []
+forbidden_sources = []
+min_size = \"\"
+max_size = \"\"
+
+#[ Trackers ]
+# trackers added to every magnet link. mode \"extend\" keeps the trackers each site provides,
+# \"replace\" drops them. file loads one tracker per line from a local file (ex: a downloaded
+# trackerslist), and a [trackers.<site>] section overrides these settings for that site
+[trackers]
+mode = \"extend\"
+list = []
+file = \"\"
+
+# [trackers.yts]
+# mode = \"replace\"
+# list = [\"udp://tracker.opentrackr.org:1337/announce\"]
+
+#[ Mirrors ]
+# ordered list of mirrors to try for each site, the next one is used when a mirror
+# can't be reached or serves a block page. Sites left out use their built-in address
+[mirrors]
+# nyaa = [\"https://nyaa.si\"]
+# piratebay = [\"https://www.tpb.party\"]
+# yts = [\"https://yts.mx\"]
+
+#[ HTTP ]
+# timeouts in seconds for connecting to a site and for each read once connected. failed requests
+# are retried on server errors (5xx) and dropped connections, waiting backoff milliseconds before
+# the first retry and twice as long before each one after it. [http.<site>] overrides these for that site
+[http]
+connect_timeout = 10
+read_timeout = 30
+retries = 2
+backoff = 500
+
+# [http.piratebay]
+# read_timeout = 60
+# retries = 4
+
+#[ Limits ]
+# concurrency caps the pages being fetched at once across all sites, which take turns page by
+# page. each host gets at most requests_per_second requests (0 for no limit) after an initial
+# burst of back to back requests
+[limits]
+concurrency = 8
+requests_per_second = 2
+burst = 4
+
+#[ Cache ]
+# pages fetched from each site are reused for ttl seconds, so repeating a search while adjusting
+# filters doesn't fetch them again (0 turns the cache off). older pages are still shown when a site
+# can't be reached, --no-cache and --refresh skip the cache for one search
+[cache]
+ttl = 900",
         )?;
 
         Ok(())
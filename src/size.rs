@@ -0,0 +1,167 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Serialize, Serializer};
+
+const BINARY_UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Size(u64);
+
+#[derive(Debug, PartialEq)]
+pub enum ParseSizeError {
+    Empty,
+    InvalidNumber(String),
+    UnknownUnit(String),
+    // a number of bytes with a decimal point, eg. "0.5" meant as 0.5 GiB
+    FractionalBytes(String),
+    TooLarge,
+}
+
+impl Size {
+    pub fn from_bytes(bytes: u64) -> Size {
+        Size(bytes)
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.0
+    }
+}
+
+impl FromStr for Size {
+    type Err = ParseSizeError;
+
+    // accepts the forms the sites display, eg. "1.4 GiB", "2.45&nbsp;GiB", "700MB" or "512 Bytes"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.replace("&nbsp;", " ").replace('\u{a0}', " ");
+        let s = s.trim();
+
+        if s.is_empty() {
+            return Err(ParseSizeError::Empty);
+        }
+
+        let split = s
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+            .unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+
+        let multiplier = unit_multiplier(unit.trim())?;
+        // bytes can't be split, a fraction usually means the unit was left out
+        if multiplier == 1 && number.contains('.') {
+            return Err(ParseSizeError::FractionalBytes(String::from(number)));
+        }
+        let (mantissa, scale) = parse_decimal(number)?;
+
+        let bytes = mantissa
+            .checked_mul(multiplier)
+            .ok_or(ParseSizeError::TooLarge)?
+            / scale;
+
+        if bytes > u64::MAX as u128 {
+            return Err(ParseSizeError::TooLarge);
+        }
+
+        Ok(Size(bytes as u64))
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut size = self.0 as f64;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < BINARY_UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+
+        if unit == 0 {
+            write!(f, "{} {}", self.0, BINARY_UNITS[0])
+        } else {
+            write!(f, "{:.1} {}", size, BINARY_UNITS[unit])
+        }
+    }
+}
+
+// sizes are written out as a number of bytes
+impl Serialize for Size {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+impl fmt::Debug for Size {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Size({})", self.0)
+    }
+}
+
+impl fmt::Display for ParseSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseSizeError::Empty => write!(f, "size is empty"),
+            ParseSizeError::InvalidNumber(n) => write!(f, "invalid size number '{}'", n),
+            ParseSizeError::UnknownUnit(u) => write!(f, "unknown size unit '{}'", u),
+            ParseSizeError::FractionalBytes(n) => {
+                write!(
+                    f,
+                    "'{}' is a fraction of a byte, add a unit (ex: 700MB, 1.5GiB)",
+                    n
+                )
+            }
+            ParseSizeError::TooLarge => write!(f, "size is too large"),
+        }
+    }
+}
+
+impl std::error::Error for ParseSizeError {}
+
+// decimal (SI) and binary (IEC) units are kept apart, "GB" is 10^9 and "GiB" is 2^30
+fn unit_multiplier(unit: &str) -> Result<u128, ParseSizeError> {
+    let multiplier = match unit.to_lowercase().as_str() {
+        "" | "b" | "byte" | "bytes" => 1,
+        "kb" => 1000,
+        "mb" => 1000u128.pow(2),
+        "gb" => 1000u128.pow(3),
+        "tb" => 1000u128.pow(4),
+        "pb" => 1000u128.pow(5),
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        "tib" => 1 << 40,
+        "pib" => 1 << 50,
+        _ => return Err(ParseSizeError::UnknownUnit(String::from(unit))),
+    };
+
+    Ok(multiplier)
+}
+
+// parses "1,024.5" into (10245, 10) so sizes are computed without float rounding
+fn parse_decimal(number: &str) -> Result<(u128, u128), ParseSizeError> {
+    let invalid = || ParseSizeError::InvalidNumber(String::from(number));
+
+    let (whole, fraction) = match number.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (number, ""),
+    };
+
+    if (whole.is_empty() && fraction.is_empty())
+        || fraction.contains(['.', ','])
+        || fraction.len() > 18
+    {
+        return Err(invalid());
+    }
+
+    // commas only group thousands, "1,5 GB" is a decimal comma rather than 15 GB
+    let mut groups = whole.split(',');
+    let first = groups.next().unwrap_or("");
+    if whole.contains(',') && (first.is_empty() || first.len() > 3 || groups.any(|g| g.len() != 3))
+    {
+        return Err(invalid());
+    }
+    let whole = whole.replace(',', "");
+
+    let digits = format!("{}{}", whole, fraction);
+    let mantissa: u128 = digits.parse().map_err(|_| invalid())?;
+
+    Ok((mantissa, 10u128.pow(fraction.len() as u32)))
+}
@@ -0,0 +1,108 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use crate::{Order, Sort, SortKey, Torrent};
+
+impl FromStr for Sort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "size" => Ok(Sort::Size),
+            "seeds" | "seeders" => Ok(Sort::Seeds),
+            "leechers" | "leech" | "peers" => Ok(Sort::Leechers),
+            "date" | "uploaded" => Ok(Sort::Date),
+            "title" | "name" => Ok(Sort::Title),
+            "source" | "site" => Ok(Sort::Source),
+            "ratio" => Ok(Sort::Ratio),
+            "resolution" | "quality" => Ok(Sort::Resolution),
+            "score" => Ok(Sort::Score),
+            "relevance" => Ok(Sort::Relevance),
+            _ => Err(format!(
+                "Unknown sort key '{}', supported keys: seeds, size, leechers, date, title, source, ratio, resolution, score, relevance",
+                s.trim()
+            )),
+        }
+    }
+}
+
+impl Sort {
+    // numbers and dates read best largest/newest first, text alphabetically
+    pub fn default_order(&self) -> Order {
+        match self {
+            Sort::Title | Sort::Source => Order::Ascending,
+            _ => Order::Descending,
+        }
+    }
+
+    fn compare(&self, a: &Torrent, b: &Torrent) -> Ordering {
+        match self {
+            Sort::Size => a.size.cmp(&b.size),
+            Sort::Seeds => a.seeders.cmp(&b.seeders),
+            Sort::Leechers => a.leechers.cmp(&b.leechers),
+            Sort::Date => a.uploaded.cmp(&b.uploaded),
+            Sort::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            Sort::Source => a.sources.join(",").cmp(&b.sources.join(",")),
+            Sort::Ratio => ratio(a).total_cmp(&ratio(b)),
+            Sort::Resolution => a.release.resolution.cmp(&b.release.resolution),
+            Sort::Score => a.score.cmp(&b.score),
+            Sort::Relevance => a.relevance.cmp(&b.relevance),
+        }
+    }
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    // "seeds", "seeds:asc" or "seeds:desc"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (sort, order) = match s.split_once(':') {
+            Some((sort, order)) => (sort, Some(order)),
+            None => (s, None),
+        };
+
+        let sort: Sort = sort.parse()?;
+        let order = match order.map(|o| o.trim().to_lowercase()) {
+            None => sort.default_order(),
+            Some(o) if o == "asc" => Order::Ascending,
+            Some(o) if o == "desc" => Order::Descending,
+            Some(o) => return Err(format!("Unknown sort order '{}', use asc or desc", o)),
+        };
+
+        Ok(SortKey { sort, order })
+    }
+}
+
+// parses a comma separated list of keys, eg. "seeds:desc,size:asc"
+pub fn parse_sort_keys(s: &str) -> Result<Vec<SortKey>, String> {
+    let keys = s
+        .split(',')
+        .filter(|key| !key.trim().is_empty())
+        .map(str::parse)
+        .collect::<Result<Vec<SortKey>, String>>()?;
+
+    if keys.is_empty() {
+        return Err(String::from("No sort keys given"));
+    }
+
+    Ok(keys)
+}
+
+pub fn sort_torrents(torrents: &mut [Torrent], keys: &[SortKey]) {
+    torrents.sort_by(|a, b| compare_torrents(a, b, keys));
+}
+
+// later keys only break ties left by earlier ones
+pub fn compare_torrents(a: &Torrent, b: &Torrent, keys: &[SortKey]) -> Ordering {
+    keys.iter()
+        .map(|key| match key.order {
+            Order::Ascending => key.sort.compare(a, b),
+            Order::Descending => key.sort.compare(b, a),
+        })
+        .find(|ordering| *ordering != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+fn ratio(torrent: &Torrent) -> f64 {
+    torrent.seeders as f64 / torrent.leechers.max(1) as f64
+}
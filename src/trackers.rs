@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::magnet::Magnet;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TrackerMode {
+    // add the configured trackers to the ones the site provides
+    Extend,
+    // drop the site's trackers and only use the configured ones
+    Replace,
+}
+
+#[derive(Clone)]
+pub struct TrackerList {
+    pub mode: TrackerMode,
+    pub trackers: Vec<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct Trackers {
+    pub global: TrackerList,
+    // per provider lists take the place of the global list for that provider
+    pub providers: HashMap<String, TrackerList>,
+}
+
+impl Default for TrackerList {
+    fn default() -> Self {
+        TrackerList {
+            mode: TrackerMode::Extend,
+            trackers: Vec::new(),
+        }
+    }
+}
+
+impl TrackerMode {
+    pub fn new(s: &str) -> Result<TrackerMode, String> {
+        match s.trim().to_lowercase().as_str() {
+            "extend" => Ok(TrackerMode::Extend),
+            "replace" => Ok(TrackerMode::Replace),
+            _ => Err(format!(
+                "Unknown tracker mode '{}', supported modes: extend, replace",
+                s
+            )),
+        }
+    }
+}
+
+impl TrackerList {
+    pub fn apply(&self, magnet: &str) -> String {
+        if self.mode == TrackerMode::Extend && self.trackers.is_empty() {
+            return String::from(magnet);
+        }
+
+        let mut magnet: Magnet = match magnet.parse() {
+            Ok(m) => m,
+            Err(_) => return String::from(magnet),
+        };
+
+        if self.mode == TrackerMode::Replace {
+            magnet.trackers.clear();
+        }
+        for tracker in &self.trackers {
+            magnet.add_tracker(tracker);
+        }
+
+        magnet.to_string()
+    }
+}
+
+impl Trackers {
+    pub fn for_provider(&self, name: &str) -> &TrackerList {
+        self.providers.get(name).unwrap_or(&self.global)
+    }
+}
+
+// reads a trackerslist style file, one tracker per line with blank lines between them
+pub fn read_tracker_file(path: &Path) -> Result<Vec<String>, io::Error> {
+    let contents = fs::read_to_string(path)?;
+
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
@@ -0,0 +1,348 @@
+use std::collections::HashSet;
+use std::io;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::output;
+use crate::sort;
+use crate::{Order, Sort, SortKey, Torrent};
+
+// keys cycled through with 's', results start out in the order they were searched with
+const SORT_CYCLE: [Sort; 9] = [
+    Sort::Seeds,
+    Sort::Score,
+    Sort::Relevance,
+    Sort::Size,
+    Sort::Resolution,
+    Sort::Date,
+    Sort::Leechers,
+    Sort::Ratio,
+    Sort::Title,
+];
+
+const PAGE_SIZE: usize = 10;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Action {
+    Continue,
+    Quit,
+    // send the selected torrents on to the torrent client
+    Confirm,
+}
+
+pub struct Browser<'a> {
+    torrents: &'a [Torrent],
+    // indices into torrents of the shown rows, after filtering and sorting
+    visible: Vec<usize>,
+    selected: HashSet<usize>,
+    filter: String,
+    // keys typed while filtering edit the filter instead of moving around
+    filtering: bool,
+    sort: Option<usize>,
+    order: Order,
+    state: TableState,
+}
+
+impl<'a> Browser<'a> {
+    pub fn new(torrents: &'a [Torrent]) -> Browser<'a> {
+        let mut browser = Browser {
+            torrents,
+            visible: (0..torrents.len()).collect(),
+            selected: HashSet::new(),
+            filter: String::new(),
+            filtering: false,
+            sort: None,
+            order: Order::Descending,
+            state: TableState::default(),
+        };
+        browser.refresh();
+
+        browser
+    }
+
+    pub fn visible(&self) -> &[usize] {
+        &self.visible
+    }
+
+    // index of the torrent under the cursor
+    pub fn current(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .and_then(|row| self.visible.get(row).copied())
+    }
+
+    // the marked torrents, or the one under the cursor when nothing is marked
+    pub fn selection(&self) -> Vec<usize> {
+        if self.selected.is_empty() {
+            return self.current().into_iter().collect();
+        }
+
+        let mut selection: Vec<usize> = self.selected.iter().copied().collect();
+        selection.sort_unstable();
+        selection
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+
+        if self.filtering {
+            match key.code {
+                KeyCode::Enter => self.filtering = false,
+                KeyCode::Esc => {
+                    self.filtering = false;
+                    self.filter.clear();
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Char(c) => self.filter.push(c),
+                _ => return Action::Continue,
+            }
+            self.refresh();
+            return Action::Continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => return Action::Quit,
+            KeyCode::Esc if self.filter.is_empty() => return Action::Quit,
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.refresh();
+            }
+            KeyCode::Enter => return Action::Confirm,
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+            KeyCode::PageDown => self.move_cursor(PAGE_SIZE as isize),
+            KeyCode::PageUp => self.move_cursor(-(PAGE_SIZE as isize)),
+            KeyCode::Home | KeyCode::Char('g') => self.move_cursor(isize::MIN),
+            KeyCode::End | KeyCode::Char('G') => self.move_cursor(isize::MAX),
+            KeyCode::Char(' ') => {
+                if let Some(current) = self.current() {
+                    if !self.selected.remove(&current) {
+                        self.selected.insert(current);
+                    }
+                    self.move_cursor(1);
+                }
+            }
+            KeyCode::Char('a') => {
+                if self.visible.iter().all(|i| self.selected.contains(i)) {
+                    for i in &self.visible {
+                        self.selected.remove(i);
+                    }
+                } else {
+                    self.selected.extend(self.visible.iter().copied());
+                }
+            }
+            KeyCode::Char('/') => self.filtering = true,
+            KeyCode::Char('s') => {
+                self.sort = match self.sort {
+                    None => Some(0),
+                    Some(i) if i + 1 < SORT_CYCLE.len() => Some(i + 1),
+                    Some(_) => None,
+                };
+                if let Some(i) = self.sort {
+                    self.order = SORT_CYCLE[i].default_order();
+                }
+                self.refresh();
+            }
+            KeyCode::Char('r') => {
+                self.order = match self.order {
+                    Order::Ascending => Order::Descending,
+                    Order::Descending => Order::Ascending,
+                };
+                self.refresh();
+            }
+            _ => {}
+        }
+
+        Action::Continue
+    }
+
+    // reapplies the filter and sort key, keeping the cursor on the same torrent when possible
+    fn refresh(&mut self) {
+        let current = self.current();
+        let terms: Vec<String> = self
+            .filter
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+
+        self.visible = (0..self.torrents.len())
+            .filter(|&i| {
+                let title = self.torrents[i].title.to_lowercase();
+                terms.iter().all(|term| title.contains(term.as_str()))
+            })
+            .collect();
+
+        if let Some(i) = self.sort {
+            let keys = [SortKey {
+                sort: SORT_CYCLE[i],
+                order: self.order,
+            }];
+            let torrents = self.torrents;
+            self.visible
+                .sort_by(|&a, &b| sort::compare_torrents(&torrents[a], &torrents[b], &keys));
+        } else if self.order == Order::Ascending {
+            self.visible.reverse();
+        }
+
+        let row = current
+            .and_then(|c| self.visible.iter().position(|&i| i == c))
+            .or(if self.visible.is_empty() {
+                None
+            } else {
+                Some(0)
+            });
+        self.state.select(row);
+    }
+
+    fn move_cursor(&mut self, by: isize) {
+        if self.visible.is_empty() {
+            return;
+        }
+
+        let last = self.visible.len() - 1;
+        let row = self.state.selected().unwrap_or(0) as isize;
+        let row = row.saturating_add(by).clamp(0, last as isize) as usize;
+        self.state.select(Some(row));
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [table_area, detail_area, status_area] = Layout::vertical([
+            Constraint::Min(5),
+            Constraint::Length(9),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        self.draw_table(frame, table_area);
+        self.draw_details(frame, detail_area);
+
+        let sort = match self.sort {
+            Some(i) => format!("{:?}", SORT_CYCLE[i]).to_lowercase(),
+            None => String::from("search order"),
+        };
+        let order = match self.order {
+            Order::Ascending => "asc",
+            Order::Descending => "desc",
+        };
+        let status = if self.filtering {
+            format!("/{}_   enter keep filter  esc clear filter", self.filter)
+        } else {
+            format!(
+                "{} of {} shown  {} marked  sort: {} {}  filter: {}   space mark  a mark all  / filter  s sort  r reverse  enter send  q quit",
+                self.visible.len(),
+                self.torrents.len(),
+                self.selected.len(),
+                sort,
+                order,
+                if self.filter.is_empty() { "none" } else { &self.filter },
+            )
+        };
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    fn draw_table(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = self.visible.iter().map(|&i| {
+            let t = &self.torrents[i];
+            let marked = if self.selected.contains(&i) {
+                "[x]"
+            } else {
+                "[ ]"
+            };
+            let uploaded = t
+                .uploaded
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+
+            Row::new(vec![
+                String::from(marked),
+                t.title.clone(),
+                t.release.summary(),
+                t.size.to_string(),
+                t.seeders.to_string(),
+                t.leechers.to_string(),
+                uploaded,
+                t.sources.join(", "),
+            ])
+        });
+
+        let widths = [
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(18),
+            Constraint::Length(10),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(10),
+            Constraint::Length(12),
+        ];
+
+        let header = Row::new(vec![
+            "", "Name", "Quality", "Size", "Seeds", "Leech", "Date", "Site",
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::bordered().title(" magnetfinder "))
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+
+    fn draw_details(&self, frame: &mut Frame, area: Rect) {
+        let lines = match self.current() {
+            Some(i) => output::details(&self.torrents[i])
+                .into_iter()
+                .map(Line::from)
+                .collect(),
+            None => vec![Line::from("No torrents match the filter")],
+        };
+
+        let details = Paragraph::new(lines)
+            .block(Block::bordered().title(" Details "))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(details, area);
+    }
+}
+
+// shows the results full screen until the user sends a selection or quits, quitting selects nothing
+pub fn browse(torrents: &[Torrent]) -> io::Result<Vec<&String>> {
+    let mut terminal = ratatui::init();
+    let selection = run_browser(&mut terminal, torrents);
+    ratatui::restore();
+
+    Ok(selection?
+        .into_iter()
+        .map(|i| &torrents[i].magnet)
+        .collect())
+}
+
+fn run_browser(terminal: &mut DefaultTerminal, torrents: &[Torrent]) -> io::Result<Vec<usize>> {
+    let mut browser = Browser::new(torrents);
+
+    loop {
+        terminal.draw(|frame| browser.draw(frame))?;
+
+        if let Event::Key(key) = event::read()? {
+            // windows also reports key releases
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match browser.handle_key(key) {
+                Action::Continue => {}
+                Action::Quit => return Ok(Vec::new()),
+                Action::Confirm => return Ok(browser.selection()),
+            }
+        }
+    }
+}
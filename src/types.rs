@@ -1,56 +1,93 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::cache::Cache;
+use crate::episode::EpisodeFilter;
+use crate::filter::Filter;
+use crate::http::HttpConfig;
+use crate::limits::Limits;
+use crate::output::Template;
+use crate::profile::{Profile, Profiles};
+use crate::provider::Provider;
+use crate::release::Release;
+use crate::size::Size;
+use crate::trackers::Trackers;
+
+#[derive(Clone, Default, Serialize)]
 pub struct Torrent {
     pub title: String,
     pub magnet: String,
-    pub size: String,
-    pub seeders: String,
-}
-
-impl Torrent {
-    pub fn get_size_as_i64(&self) -> i64 {
-        let split: Vec<&str> = self.size.split(' ').collect();
-
-        if split.len() < 2 {
-            let float: f32 = split[0].parse().unwrap_or(0.0);
-            let int_approximation = (float * 100.0) as i64;
-            return int_approximation;
-        }
-
-        let base: i64 = 2;
-        let byte_modifier = match split[1] {
-            "TiB" | "TB" => base.pow(40),
-            "GiB" | "GB" => base.pow(30),
-            "MiB" | "MB" => base.pow(20),
-            "KiB" | "KB" => base.pow(10),
-            "Bytes" => 1,
-            _ => 1,
-        };
-
-        let float: f32 = split[0].parse().unwrap_or(0.0);
-        let int_approximation = (float * 100.0) as i64;
-
-        int_approximation * byte_modifier
-    }
-}
-
-pub enum Website {
-    Nyaa,
-    Piratebay,
-    YTS,
+    pub size: Size,
+    pub seeders: u32,
+    pub leechers: u32,
+    pub uploaded: Option<DateTime<Utc>>,
+    pub uploader: Option<String>,
+    pub category: Option<String>,
+    // names of the providers the torrent was found on
+    pub sources: Vec<&'static str>,
+    // normalized info hash taken from the magnet link, see Magnet::key
+    pub info_hash: Option<String>,
+    // torrent's detail page on the site it was scraped from
+    pub url: Option<String>,
+    // resolution, codecs, episode numbers etc. parsed from the title
+    pub release: Release,
+    // how well the torrent fits the quality profile for the searched media
+    pub score: i32,
+    // 0-100 match of the title against the search query, see relevance::relevance
+    pub relevance: u32,
 }
 
+#[derive(Clone, Copy, PartialEq)]
 pub enum Media {
     Anime,
     Movie,
     TVShow,
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Sort {
     Size,
     Seeds,
+    Leechers,
+    Date,
+    Title,
+    Source,
+    Ratio,
+    Resolution,
+    Score,
+    Relevance,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Order {
+    Ascending,
+    Descending,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SortKey {
+    pub sort: Sort,
+    pub order: Order,
+}
+
+// how results are printed by --no-interactive
+#[derive(Clone, PartialEq, Debug)]
+pub enum OutputFormat {
+    // one "title<tab>magnet" line per torrent
+    Text,
+    Json,
+    // one json object per line
+    Ndjson,
+    // header row followed by one row per torrent
+    Csv,
+    // one line per torrent, set with --format
+    Template(Template),
 }
 
 pub enum TorrentClient {
@@ -67,18 +104,48 @@ pub struct Settings {
     pub default_directory: Rc<PathBuf>,
     pub default_proxy: String,
     pub autodownload: bool,
+    pub tui: bool,
+    // seconds to wait for a site before showing results without it, 0 waits forever
+    pub timeout: u64,
     pub torrent_client: String,
+    pub mirrors: HashMap<String, Vec<String>>,
+    pub http: HttpConfig,
+    pub limits: Limits,
+    // seconds a fetched page is reused for, 0 turns the cache off
+    pub cache_ttl: u64,
+    pub trackers: Trackers,
+    pub filter: Filter,
+    pub profiles: Profiles,
 }
 
 pub struct UserParameters {
-    pub websites: Vec<Website>,
+    pub providers: Vec<Arc<dyn Provider>>,
     pub directory: Rc<PathBuf>,
     pub search_query: Arc<String>,
-    pub search_depth: u32,
-    pub sort_preference: Sort,
+    // pages to read from each site, None reads until a site runs out of results
+    pub search_depth: Option<u32>,
+    // None waits for every site however long it takes
+    pub timeout: Option<Duration>,
+    // set by an episode search, results are narrowed down to these episodes
+    pub episodes: Option<EpisodeFilter>,
+    pub sort_preference: Vec<SortKey>,
     pub num_torrents_shown: usize,
-    pub proxy: Arc<String>,
+    // checked up front, scraping never goes out without a proxy that was asked for
+    pub proxy: Option<ureq::Proxy>,
+    pub http: HttpConfig,
+    pub limits: Limits,
+    // None when caching is turned off
+    pub cache: Option<Arc<Cache>>,
+    pub mirrors: HashMap<String, Vec<String>>,
+    pub trackers: Trackers,
+    pub filter: Filter,
+    // quality profile of the searched media, empty when the media isn't known
+    pub profile: Profile,
     pub autodownload: bool,
     pub torrent_client: TorrentClient,
     pub no_interactive: bool,
+    pub tui: bool,
+    // prints every page fetched and mirror skipped after the search
+    pub verbose: bool,
+    pub output: OutputFormat,
 }
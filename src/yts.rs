@@ -1,10 +1,12 @@
-use std::sync::{mpsc::Sender, Arc};
-use std::thread;
-
+use chrono::{TimeZone, Utc};
 use serde::Deserialize;
-use ureq::Agent;
 
-use crate::Torrent;
+use crate::error::Error;
+use crate::magnet::Magnet;
+use crate::provider::{Capabilities, ParsedPage, Provider};
+use crate::release::Release;
+use crate::size::Size;
+use crate::{Media, Torrent};
 
 #[allow(dead_code)]
 #[derive(Deserialize)]
@@ -13,10 +15,10 @@ struct YTSTorrent {
     hash: String,
     quality: String,
     r#type: String,
-    seeds: usize,
-    peers: usize,
+    seeds: u32,
+    peers: u32,
     size: String,
-    size_bytes: usize,
+    size_bytes: u64,
     date_uploaded: String,
     date_uploaded_unix: i64,
 }
@@ -53,6 +55,8 @@ struct YTSData {
     movie_count: usize,
     limit: usize,
     page_number: usize,
+    // omitted by the api once a search runs past its last page
+    #[serde(default)]
     movies: Vec<YTSMovie>,
 }
 
@@ -64,75 +68,117 @@ struct YTSResponse {
     data: YTSData,
 }
 
-pub fn query(client: &Arc<Agent>, tx: Sender<Vec<Torrent>>, query: &Arc<String>, depth: u32) {
-    for page in 1..=depth {
-        let t_tx = Sender::clone(&tx);
-        let t_client = Arc::clone(client);
-        let t_query = Arc::clone(query);
-
-        thread::spawn(move || {
-            let torrents = fetch_page_results(&t_client, &t_query, page).unwrap_or_else(|err| {
-                // json decode errors will occur when search_depth is greater than
-                // the number of pages yts has for a search, ignore error messages for this
-                if !err.to_string().contains("Failed to read JSON") {
-                    eprintln!("Error requesting data from yts: {:?}", err);
-                }
+pub struct Yts;
 
-                vec![]
-            });
+impl Provider for Yts {
+    fn name(&self) -> &'static str {
+        "yts"
+    }
 
-            t_tx.send(torrents).unwrap();
-        });
+    fn short_flag(&self) -> &'static str {
+        "y"
+    }
+
+    fn help(&self) -> &'static str {
+        "get torrents from YIFY/YTS"
     }
-}
 
-pub fn fetch_page_results(
-    client: &Agent,
-    query: &str,
-    page_number: u32,
-) -> Result<Vec<Torrent>, ureq::Error> {
-    let mut results = Vec::new();
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            media: &[Media::Movie],
+            episode_naming: None,
+        }
+    }
 
-    let formatted_query = query.replace(" ", "+");
-    let url = format!(
-        "https://yts.mx/api/v2/list_movies.json?query_term={}&page={}",
-        formatted_query, page_number
-    );
-    let body = client.get(&url).call()?.into_json::<YTSResponse>()?;
+    fn default_mirrors(&self) -> &'static [&'static str] {
+        &["https://yts.mx"]
+    }
 
-    for movie in body.data.movies {
-        let title = movie.title_long;
-        let slug = movie.slug;
+    fn search_url(&self, mirror: &str, query: &str, page_number: u32) -> String {
+        let formatted_query = query.replace(" ", "+");
+        format!(
+            "{}/api/v2/list_movies.json?query_term={}&page={}",
+            mirror, formatted_query, page_number
+        )
+    }
 
+    fn parse_page(&self, body: &str) -> Result<ParsedPage, Error> {
+        Ok(parse_page(body)?)
+    }
+}
+
+pub fn parse_page(body: &str) -> Result<ParsedPage, serde_json::Error> {
+    let mut page = ParsedPage::default();
+
+    let response: YTSResponse = serde_json::from_str(body)?;
+
+    for movie in response.data.movies {
         for torrent in movie.torrents {
-            results.push(Torrent {
-                title: title.clone(),
-                magnet: make_magnet(torrent.hash, slug.clone()),
-                size: torrent.size,
-                seeders: torrent.seeds.to_string(),
+            // movies have one torrent per quality, named the way yts names its releases
+            let title = format!(
+                "{} [{}] [{}]",
+                movie.title_long,
+                torrent.quality,
+                release_type(&torrent.r#type)
+            );
+
+            let magnet = match make_magnet(&torrent.hash, &title) {
+                Some(magnet) => magnet,
+                None => {
+                    page.skipped += 1;
+                    continue;
+                }
+            };
+
+            page.torrents.push(Torrent {
+                release: Release::parse(&title),
+                title,
+                info_hash: magnet.key().map(String::from),
+                magnet: magnet.to_string(),
+                size: Size::from_bytes(torrent.size_bytes),
+                seeders: torrent.seeds,
+                leechers: torrent.peers,
+                uploaded: Utc.timestamp_opt(torrent.date_uploaded_unix, 0).single(),
+                // every release on yts is encoded and uploaded by yts itself
+                uploader: Some(String::from("YTS")),
+                category: Some(String::from("Movies")),
+                sources: vec!["yts"],
+                url: Some(movie.url.clone()),
+                score: 0,
+                relevance: 0,
             });
         }
     }
 
-    Ok(results)
+    page.last = response.data.page_number * response.data.limit >= response.data.movie_count;
+
+    Ok(page)
 }
 
-fn make_magnet(info_hash: String, name: String) -> String {
-    // recommended trackers from YTS API docs (https://yts.mx/api)
+fn release_type(api_type: &str) -> &str {
+    match api_type {
+        "bluray" => "BluRay",
+        "web" => "WEBRip",
+        _ => api_type,
+    }
+}
+
+fn make_magnet(info_hash: &str, name: &str) -> Option<Magnet> {
+    // the api only returns info hashes, these are replaced or extended by the
+    // trackers configured in Settings.toml
     let trackers = [
-        "udp://open.demonii.com:1337/announce",
-        "udp://tracker.openbittorrent.com:80",
-        "udp://tracker.coppersurfer.tk:6969",
-        "udp://glotorrents.pw:6969/announce",
         "udp://tracker.opentrackr.org:1337/announce",
-        "udp://torrent.gresille.org:80/announce",
-        "udp://p4p.arenabg.com:1337",
-        "udp://tracker.leechers-paradise.org:6969",
-    ]
-    .join("&tr=");
-
-    format!(
-        "magnet:?xt=urn:btih:{}&dn={}&tr={}",
-        info_hash, name, trackers
-    )
+        "udp://open.stealth.si:80/announce",
+        "udp://tracker.torrent.eu.org:451/announce",
+        "udp://exodus.desync.com:6969/announce",
+        "udp://p4p.arenabg.com:1337/announce",
+    ];
+
+    let mut magnet = Magnet::new(info_hash).ok()?;
+    magnet.name = Some(String::from(name));
+    for tracker in &trackers {
+        magnet.add_tracker(tracker);
+    }
+
+    Some(magnet)
 }
@@ -0,0 +1,89 @@
+mod common;
+
+use std::fs;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use magnetfinder::cache::{self, Cache};
+
+use common::TempDir;
+
+const URL: &str = "https://nyaa.si/?q=frieren&p=1";
+
+#[test]
+fn reads_back_stored_pages() {
+    let dir = TempDir::new("cache-stored");
+    let cache = Cache::new(dir.path().to_path_buf(), Duration::from_secs(60), false);
+
+    assert_eq!(cache.get(URL), None);
+    cache.put(URL, 200, "<html>\nresults\n</html>");
+
+    assert_eq!(
+        cache.get(URL),
+        Some((200, String::from("<html>\nresults\n</html>")))
+    );
+    assert_eq!(cache.get("https://nyaa.si/?q=frieren&p=2"), None);
+}
+
+#[test]
+fn expired_pages_are_only_used_stale() {
+    let dir = TempDir::new("cache-expired");
+    let cache = Cache::new(dir.path().to_path_buf(), Duration::from_secs(0), false);
+    cache.put(URL, 200, "results");
+
+    assert_eq!(cache.get(URL), None);
+    assert_eq!(cache.get_stale(URL), Some((200, String::from("results"))));
+}
+
+#[test]
+fn refresh_stores_without_reading() {
+    let dir = TempDir::new("cache-refresh");
+    let refreshing = Cache::new(dir.path().to_path_buf(), Duration::from_secs(60), true);
+    refreshing.put(URL, 200, "results");
+
+    assert_eq!(refreshing.get(URL), None);
+    assert_eq!(refreshing.get_stale(URL), None);
+
+    let cache = Cache::new(dir.path().to_path_buf(), Duration::from_secs(60), false);
+    assert_eq!(cache.get(URL), Some((200, String::from("results"))));
+}
+
+#[test]
+fn clear_removes_every_page() {
+    let dir = TempDir::new("cache-clear");
+    assert_eq!(cache::clear(dir.path()).unwrap(), 0);
+
+    let cache = Cache::new(dir.path().to_path_buf(), Duration::from_secs(60), false);
+    cache.put(URL, 200, "results");
+    cache.put("https://yts.mx/api/v2/list_movies.json", 200, "{}");
+
+    assert_eq!(cache::clear(dir.path()).unwrap(), 2);
+    assert_eq!(cache.get(URL), None);
+}
+
+#[test]
+fn pages_stored_at_once_stay_whole() {
+    let dir = TempDir::new("cache-concurrent");
+    let cache = Arc::new(Cache::new(
+        dir.path().to_path_buf(),
+        Duration::from_secs(60),
+        false,
+    ));
+
+    let writers: Vec<_> = (0..8)
+        .map(|n| {
+            let cache = Arc::clone(&cache);
+            thread::spawn(move || cache.put(URL, 200, &n.to_string().repeat(100_000)))
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    let (_, body) = cache.get(URL).unwrap();
+    assert_eq!(body.len(), 100_000);
+    assert!(body.chars().all(|c| c == body.chars().next().unwrap()));
+    // no partial files are left behind
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+}
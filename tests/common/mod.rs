@@ -0,0 +1,46 @@
+// helpers shared by the test files, each file only uses some of them
+#![allow(dead_code)]
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use magnetfinder::release::Release;
+use magnetfinder::types::Torrent;
+
+// a directory of its own for each test, removed once the test is done
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    // name has to be unique across the test files, they run at once
+    pub fn new(name: &str) -> TempDir {
+        let dir = env::temp_dir().join(format!("magnetfinder-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        TempDir(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+// a result the way the sites' results are read, release included
+pub fn torrent(title: &str, size: &str, seeders: u32) -> Torrent {
+    Torrent {
+        title: String::from(title),
+        size: size.parse().unwrap(),
+        seeders,
+        release: Release::parse(title),
+        ..Default::default()
+    }
+}
+
+pub fn titles(torrents: &[Torrent]) -> Vec<&str> {
+    torrents.iter().map(|t| t.title.as_str()).collect()
+}
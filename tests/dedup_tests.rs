@@ -0,0 +1,71 @@
+mod common;
+
+use magnetfinder::dedup;
+use magnetfinder::types::Torrent;
+
+use common::torrent;
+
+const HASH: &str = "8c3f4b9d1a2e3f4a5b6c7d8e9f0a1b2c3d4e5f60";
+
+// the same release as listed by source
+fn found_on(source: &'static str, seeders: u32, trackers: &[&str]) -> Torrent {
+    let mut magnet = format!("magnet:?xt=urn:btih:{}&dn=release", HASH);
+    for tracker in trackers {
+        magnet.push_str("&tr=");
+        magnet.push_str(tracker);
+    }
+
+    Torrent {
+        magnet,
+        sources: vec![source],
+        info_hash: Some(String::from(HASH)),
+        ..torrent(&format!("release from {}", source), "0 B", seeders)
+    }
+}
+
+#[test]
+fn merges_torrents_with_same_info_hash() {
+    let torrents = vec![
+        found_on("nyaa", 120, &["udp%3A%2F%2Fa.example%3A80"]),
+        found_on("piratebay", 450, &["udp%3A%2F%2Fb.example%3A80"]),
+    ];
+
+    let merged = dedup::merge_duplicates(torrents);
+
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].title, "release from nyaa");
+    assert_eq!(merged[0].sources, vec!["nyaa", "piratebay"]);
+    assert_eq!(merged[0].seeders, 450);
+}
+
+#[test]
+fn combines_trackers_without_repeats() {
+    let torrents = vec![
+        found_on("nyaa", 1, &["udp%3A%2F%2Fa.example%3A80"]),
+        found_on(
+            "piratebay",
+            1,
+            &["udp%3A%2F%2Fa.example%3A80", "udp%3A%2F%2Fb.example%3A80"],
+        ),
+    ];
+
+    let merged = dedup::merge_duplicates(torrents);
+
+    assert_eq!(
+        merged[0].magnet,
+        format!(
+            "magnet:?xt=urn:btih:{}&dn=release&tr=udp%3A%2F%2Fa.example%3A80&tr=udp%3A%2F%2Fb.example%3A80",
+            HASH
+        )
+    );
+}
+
+#[test]
+fn keeps_torrents_without_info_hash() {
+    let mut first = found_on("nyaa", 1, &[]);
+    first.info_hash = None;
+    let mut second = found_on("nyaa", 1, &[]);
+    second.info_hash = None;
+
+    assert_eq!(dedup::merge_duplicates(vec![first, second]).len(), 2);
+}
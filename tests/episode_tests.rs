@@ -0,0 +1,133 @@
+use magnetfinder::episode::{parse_range, EpisodeFilter, EpisodeNaming};
+use magnetfinder::release::Release;
+
+fn filter(seasons: Option<&str>, episodes: Option<&str>, packs: bool) -> EpisodeFilter {
+    EpisodeFilter {
+        seasons: seasons.map(|s| parse_range(s).unwrap()),
+        episodes: episodes.map(|e| parse_range(e).unwrap()),
+        packs,
+    }
+}
+
+fn matches(filter: &EpisodeFilter, title: &str) -> bool {
+    filter.matches(&Release::parse(title))
+}
+
+#[test]
+fn parses_ranges() {
+    assert_eq!(parse_range("5"), Ok(5..=5));
+    assert_eq!(parse_range("1-12"), Ok(1..=12));
+    assert_eq!(parse_range(" 02 - 04 "), Ok(2..=4));
+    assert!(parse_range("5-1").is_err());
+    assert!(parse_range("five").is_err());
+    assert!(parse_range("1-").is_err());
+}
+
+#[test]
+fn scene_queries() {
+    let naming = EpisodeNaming::Scene;
+
+    assert_eq!(
+        filter(Some("2"), Some("5"), false).queries("The Expanse", naming),
+        vec!["The Expanse S02E05"]
+    );
+    assert_eq!(
+        filter(Some("2"), Some("5"), true).queries("The Expanse", naming),
+        vec!["The Expanse S02E05", "The Expanse S02"]
+    );
+    assert_eq!(
+        filter(Some("2"), Some("5-8"), false).queries("The Expanse", naming),
+        vec!["The Expanse S02"]
+    );
+    assert_eq!(
+        filter(Some("1-3"), None, false).queries("The Expanse", naming),
+        vec!["The Expanse S01", "The Expanse S02", "The Expanse S03"]
+    );
+    assert_eq!(
+        filter(None, Some("5"), false).queries("The Expanse", naming),
+        vec!["The Expanse"]
+    );
+}
+
+#[test]
+fn fansub_queries() {
+    let naming = EpisodeNaming::Fansub;
+
+    assert_eq!(
+        filter(None, Some("5"), false).queries("Tokyo Revengers", naming),
+        vec!["Tokyo Revengers 05"]
+    );
+    assert_eq!(
+        filter(Some("1"), Some("5"), true).queries("Tokyo Revengers", naming),
+        vec!["Tokyo Revengers 05", "Tokyo Revengers batch"]
+    );
+    assert_eq!(
+        filter(None, Some("1-12"), false).queries("Tokyo Revengers", naming),
+        vec!["Tokyo Revengers"]
+    );
+}
+
+#[test]
+fn matches_single_episodes() {
+    let f = filter(Some("5"), Some("10"), false);
+
+    assert!(matches(&f, "The.Expanse.S05E10.1080p.WEB.H264-GLHF"));
+    assert!(!matches(&f, "The.Expanse.S05E09.720p.WEB.x265-MiNX"));
+    assert!(!matches(&f, "The.Expanse.S04E10.720p.WEB.x265-MiNX"));
+    assert!(!matches(&f, "The.Expanse.S05.COMPLETE.720p.WEBRip"));
+    // fansub titles without a season are matched on the episode
+    assert!(matches(&f, "[SubsPlease] The Expanse - 10 (1080p)"));
+}
+
+#[test]
+fn matches_episode_ranges() {
+    let f = filter(Some("2"), Some("1-3"), false);
+
+    assert!(matches(&f, "Show.S02E01.720p.HDTV.x264-GRP"));
+    assert!(matches(&f, "Show.S02E01-E03.720p.HDTV.x264-GRP"));
+    assert!(!matches(&f, "Show.S02E02-E05.720p.HDTV.x264-GRP"));
+    assert!(!matches(&f, "Show.S02E04.720p.HDTV.x264-GRP"));
+}
+
+#[test]
+fn matches_packs_when_requested() {
+    let f = filter(Some("5"), Some("10"), true);
+
+    assert!(matches(&f, "The.Expanse.S05.COMPLETE.720p.WEBRip"));
+    assert!(!matches(&f, "The.Expanse.S04.COMPLETE.720p.WEBRip"));
+    assert!(matches(
+        &f,
+        "[Erai-raws] The Expanse - 01 ~ 12 [1080p][Batch]"
+    ));
+    assert!(matches(
+        &filter(Some("2"), Some("5"), true),
+        "[Judas] Shingeki no Kyojin (Season 2) [1080p][HEVC x265 10bit]"
+    ));
+    assert!(!matches(
+        &filter(Some("2"), Some("5"), false),
+        "[Judas] Shingeki no Kyojin (Season 2) [1080p][HEVC x265 10bit]"
+    ));
+    assert!(!matches(
+        &f,
+        "[Erai-raws] The Expanse - 11 ~ 20 [1080p][Batch]"
+    ));
+}
+
+#[test]
+fn season_only_matches_whole_season() {
+    let f = filter(Some("5"), None, false);
+
+    assert!(matches(&f, "The.Expanse.S05E10.1080p.WEB.H264-GLHF"));
+    assert!(matches(&f, "The.Expanse.S05.COMPLETE.720p.WEBRip"));
+    assert!(!matches(&f, "The.Expanse.S04E10.1080p.WEB.H264-GLHF"));
+}
+
+#[test]
+fn matches_multi_season_packs() {
+    let title = "The.Expanse.S01-S05.COMPLETE.1080p.BluRay.x264-GRP";
+
+    assert!(matches(&filter(Some("3"), None, true), title));
+    assert!(matches(&filter(Some("1-5"), None, false), title));
+    assert!(!matches(&filter(Some("3"), None, false), title));
+    assert!(!matches(&filter(Some("6"), None, true), title));
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use magnetfinder::filter::{self, Filter};
+use magnetfinder::types::Torrent;
+
+use common::{titles, torrent};
+
+fn sample() -> Vec<Torrent> {
+    vec![
+        torrent("Movie.2021.1080p.WEB-DL.x265", "2.1 GiB", 300),
+        torrent("Movie.2021.HDCAM.x264", "1.4 GiB", 900),
+        torrent("Movie.2021.720p.WEB-DL.x264", "800 MiB", 0),
+        torrent("Movie.2021.2160p.BluRay.REMUX", "58 GiB", 40),
+    ]
+}
+
+#[test]
+fn filters_by_size_range() {
+    let mut torrents = sample();
+    let filter = Filter {
+        min_size: Some("1 GiB".parse().unwrap()),
+        max_size: Some("10 GiB".parse().unwrap()),
+        ..Default::default()
+    };
+
+    filter.apply(&mut torrents);
+
+    assert_eq!(
+        titles(&torrents),
+        vec!["Movie.2021.1080p.WEB-DL.x265", "Movie.2021.HDCAM.x264"]
+    );
+}
+
+#[test]
+fn filters_by_seeders() {
+    let mut torrents = sample();
+    let filter = Filter {
+        min_seeders: Some(1),
+        ..Default::default()
+    };
+
+    filter.apply(&mut torrents);
+
+    assert!(titles(&torrents)
+        .iter()
+        .all(|title| *title != "Movie.2021.720p.WEB-DL.x264"));
+}
+
+#[test]
+fn filters_by_title_regex() {
+    let mut torrents = sample();
+    let filter = Filter {
+        include: Some(filter::build_regex(r"web-dl|bluray").unwrap()),
+        exclude: Some(filter::build_regex(r"\bcam\b|hdcam|x264").unwrap()),
+        ..Default::default()
+    };
+
+    filter.apply(&mut torrents);
+
+    assert_eq!(
+        titles(&torrents),
+        vec![
+            "Movie.2021.1080p.WEB-DL.x265",
+            "Movie.2021.2160p.BluRay.REMUX"
+        ]
+    );
+}
+
+#[test]
+fn empty_filter_keeps_everything() {
+    let mut torrents = sample();
+
+    Filter::default().apply(&mut torrents);
+
+    assert_eq!(torrents.len(), 4);
+}
+
+#[test]
+fn rejects_invalid_regex() {
+    assert!(filter::build_regex("(unclosed").is_err());
+}
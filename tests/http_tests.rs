@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use magnetfinder::http::{Client, HttpConfig, HttpSettings};
+
+// answers requests with these statuses in turn, repeating the last one, and counts requests
+fn serve(statuses: &'static [&'static str]) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&requests);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap_or(0) > 2 {
+                line.clear();
+            }
+
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            let status = statuses[n.min(statuses.len() - 1)];
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+                status
+            );
+        }
+    });
+
+    (address, requests)
+}
+
+fn client(retries: u32) -> Client {
+    Client::new(
+        &HttpSettings {
+            retries,
+            backoff: Duration::from_millis(10),
+            ..Default::default()
+        },
+        None,
+    )
+}
+
+#[test]
+fn retries_server_errors() {
+    let (url, requests) = serve(&["503 Service Unavailable", "502 Bad Gateway", "200 OK"]);
+
+    let response = client(2).get(&url).unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn gives_up_after_the_last_retry() {
+    let (url, requests) = serve(&["500 Internal Server Error"]);
+
+    let err = client(2).get(&url).unwrap_err();
+
+    assert_eq!(err.status(), Some(500));
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn backs_off_exponentially() {
+    let (url, _) = serve(&["503 Service Unavailable"]);
+    let client = Client::new(
+        &HttpSettings {
+            retries: 2,
+            backoff: Duration::from_millis(100),
+            ..Default::default()
+        },
+        None,
+    );
+
+    let started = Instant::now();
+    assert!(client.get(&url).is_err());
+    // 100 ms, then 200 ms
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
+
+#[test]
+fn client_errors_are_not_retried() {
+    let (url, requests) = serve(&["404 Not Found"]);
+
+    assert!(client(2).get(&url).is_err());
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn hanging_sites_time_out_without_retrying() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&connections);
+    thread::spawn(move || {
+        let mut open = Vec::new();
+        for stream in listener.incoming().flatten() {
+            counter.fetch_add(1, Ordering::SeqCst);
+            // keep the connection open without ever answering
+            open.push(stream);
+        }
+    });
+
+    let client = Client::new(
+        &HttpSettings {
+            read_timeout: Duration::from_millis(200),
+            retries: 2,
+            backoff: Duration::from_millis(10),
+            ..Default::default()
+        },
+        None,
+    );
+
+    let started = Instant::now();
+    let err = client.get(&url).unwrap_err();
+
+    assert_eq!(err.status(), None);
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn sites_fall_back_to_global_settings() {
+    let piratebay = HttpSettings {
+        read_timeout: Duration::from_secs(60),
+        ..Default::default()
+    };
+    let mut providers = HashMap::new();
+    providers.insert(String::from("piratebay"), piratebay.clone());
+    let config = HttpConfig {
+        global: HttpSettings::default(),
+        providers,
+    };
+
+    assert_eq!(config.for_provider("piratebay"), &piratebay);
+    assert_eq!(config.for_provider("nyaa"), &HttpSettings::default());
+}
@@ -0,0 +1,114 @@
+use magnetfinder::magnet::{Magnet, MagnetError};
+
+const HEX_HASH: &str = "c12fe1c06bba254a9dc9f519b335aa7c1367a88a";
+// same hash as HEX_HASH encoded as base32
+const BASE32_HASH: &str = "YEX6DQDLXISUVHOJ6UM3GNNKPQJWPKEK";
+const V2_HASH: &str = "1220caf1e1c30e81cb361b9ee167c4aa64228a7fa4fa9f6105232b28ad099f3a302e";
+
+#[test]
+fn parses_magnet_fields() {
+    let magnet: Magnet = format!(
+        "magnet:?xt=urn:btih:{}&dn=Some%20Show%20S01E01&xl=1073741824&tr=udp%3A%2F%2Ftracker.example%3A1337%2Fannounce&ws=https%3A%2F%2Fseed.example%2Ffile.mkv",
+        HEX_HASH
+    )
+    .parse()
+    .unwrap();
+
+    assert_eq!(magnet.info_hash.as_deref(), Some(HEX_HASH));
+    assert_eq!(magnet.name.as_deref(), Some("Some Show S01E01"));
+    assert_eq!(magnet.length, Some(1073741824));
+    assert_eq!(magnet.trackers, vec!["udp://tracker.example:1337/announce"]);
+    assert_eq!(magnet.web_seeds, vec!["https://seed.example/file.mkv"]);
+}
+
+#[test]
+fn normalizes_info_hashes() {
+    let upper: Magnet = format!("magnet:?xt=urn:btih:{}", HEX_HASH.to_uppercase())
+        .parse()
+        .unwrap();
+    let base32: Magnet = format!("magnet:?xt=urn:btih:{}", BASE32_HASH)
+        .parse()
+        .unwrap();
+
+    assert_eq!(upper.key(), Some(HEX_HASH));
+    assert_eq!(base32.key(), Some(HEX_HASH));
+}
+
+#[test]
+fn parses_v2_and_hybrid_magnets() {
+    let v2: Magnet = format!("magnet:?xt=urn:btmh:{}", V2_HASH.to_uppercase())
+        .parse()
+        .unwrap();
+    assert_eq!(v2.info_hash, None);
+    assert_eq!(v2.key(), Some(V2_HASH));
+
+    let hybrid: Magnet = format!("magnet:?xt=urn:btih:{}&xt=urn:btmh:{}", HEX_HASH, V2_HASH)
+        .parse()
+        .unwrap();
+    assert_eq!(hybrid.key(), Some(HEX_HASH));
+    assert_eq!(hybrid.info_hash_v2.as_deref(), Some(V2_HASH));
+}
+
+#[test]
+fn decodes_plus_as_space_in_name() {
+    let magnet: Magnet = format!("magnet:?xt=urn:btih:{}&dn=Some+Show", HEX_HASH)
+        .parse()
+        .unwrap();
+
+    assert_eq!(magnet.name.as_deref(), Some("Some Show"));
+}
+
+#[test]
+fn accepts_scheme_in_any_case() {
+    for scheme in ["MAGNET:?", "Magnet:?"] {
+        let magnet: Magnet = format!("{}xt=urn:btih:{}", scheme, HEX_HASH)
+            .parse()
+            .unwrap();
+        assert_eq!(magnet.key(), Some(HEX_HASH));
+    }
+}
+
+#[test]
+fn rejects_invalid_magnets() {
+    assert_eq!(
+        "https://nyaa.si/download/1.torrent".parse::<Magnet>(),
+        Err(MagnetError::NotAMagnet)
+    );
+    assert_eq!("magnet".parse::<Magnet>(), Err(MagnetError::NotAMagnet));
+    assert_eq!(
+        "magnet:?dn=no+hash".parse::<Magnet>(),
+        Err(MagnetError::MissingInfoHash)
+    );
+    assert_eq!(
+        "magnet:?xt=urn:btih:1234".parse::<Magnet>(),
+        Err(MagnetError::InvalidInfoHash(String::from("1234")))
+    );
+    assert_eq!(
+        format!("magnet:?xt=urn:btih:{}&xl=big", HEX_HASH).parse::<Magnet>(),
+        Err(MagnetError::InvalidLength(String::from("big")))
+    );
+}
+
+#[test]
+fn serializes_with_percent_encoding() {
+    let mut magnet = Magnet::new(HEX_HASH).unwrap();
+    magnet.name = Some(String::from("Star Wars: Episode IV - A New Hope (1977)"));
+    magnet.add_tracker("udp://tracker.opentrackr.org:1337/announce");
+    magnet.add_tracker("udp://tracker.opentrackr.org:1337/announce");
+
+    assert_eq!(
+        magnet.to_string(),
+        format!(
+            "magnet:?xt=urn:btih:{}&dn=Star%20Wars%3A%20Episode%20IV%20-%20A%20New%20Hope%20%281977%29&tr=udp%3A%2F%2Ftracker.opentrackr.org%3A1337%2Fannounce",
+            HEX_HASH
+        )
+    );
+}
+
+#[test]
+fn round_trips_unknown_parameters() {
+    let link = format!("magnet:?xt=urn:btih:{}&x.pe=10.0.0.1%3A6881", HEX_HASH);
+    let magnet: Magnet = link.parse().unwrap();
+
+    assert_eq!(magnet.to_string(), link);
+}
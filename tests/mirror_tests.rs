@@ -0,0 +1,361 @@
+mod common;
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::atomic::AtomicBool;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use magnetfinder::cache::Cache;
+use magnetfinder::error::Error;
+use magnetfinder::http::{Client, HttpSettings};
+use magnetfinder::limits::Pool;
+use magnetfinder::nyaa::Nyaa;
+use magnetfinder::piratebay::Piratebay;
+use magnetfinder::provider::{self, Provider};
+use magnetfinder::search::{Page, Pages};
+
+use common::TempDir;
+
+const NYAA_SEARCH: &str = include_str!("fixtures/nyaa_search.html");
+const PIRATEBAY_SEARCH: &str = include_str!("fixtures/piratebay_search.html");
+const CLOUDFLARE_CHALLENGE: &str =
+    "<!DOCTYPE html><html><head><title>Just a moment...</title></head><body></body></html>";
+
+// stand-in mirror answering every request with the same response
+fn serve(status: &'static str, body: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap_or(0) > 2 {
+                line.clear();
+            }
+
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+        }
+    });
+
+    address
+}
+
+// block pages are answered with 503, which would otherwise be retried before failing over
+fn client() -> Client {
+    Client::new(
+        &HttpSettings {
+            retries: 0,
+            ..Default::default()
+        },
+        None,
+    )
+}
+
+fn dead_mirror() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+#[test]
+fn fetches_from_configured_mirror() {
+    let mirrors = vec![serve("200 OK", NYAA_SEARCH)];
+
+    let fetched = Nyaa
+        .fetch_page(&client(), &mirrors, "tokyo revengers", 1)
+        .unwrap();
+    let results = fetched.page.torrents;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].url, Some(format!("{}/view/1426262", mirrors[0])));
+    assert_eq!(fetched.mirror, mirrors[0]);
+    assert_eq!(fetched.status, 200);
+    assert!(fetched.failovers.is_empty());
+}
+
+#[test]
+fn fails_over_unreachable_mirror() {
+    let mirrors = vec![dead_mirror(), serve("200 OK", PIRATEBAY_SEARCH)];
+
+    let fetched = Piratebay
+        .fetch_page(&client(), &mirrors, "the expanse", 1)
+        .unwrap();
+
+    assert_eq!(fetched.page.torrents.len(), 2);
+    assert_eq!(fetched.mirror, mirrors[1]);
+    assert_eq!(fetched.failovers.len(), 1);
+    assert!(fetched.failovers[0].starts_with(&mirrors[0]));
+}
+
+#[test]
+fn fails_over_block_page() {
+    let mirrors = vec![
+        serve("503 Service Unavailable", CLOUDFLARE_CHALLENGE),
+        serve("200 OK", CLOUDFLARE_CHALLENGE),
+        serve("200 OK", NYAA_SEARCH),
+    ];
+
+    let fetched = Nyaa
+        .fetch_page(&client(), &mirrors, "tokyo revengers", 1)
+        .unwrap();
+
+    assert_eq!(fetched.page.torrents.len(), 2);
+    assert_eq!(fetched.failovers.len(), 2);
+    assert!(fetched
+        .failovers
+        .iter()
+        .all(|failover| failover.ends_with("mirror returned a block page")));
+}
+
+#[test]
+fn fails_over_page_without_results() {
+    // eg. a lapsed mirror domain now serving a parking page
+    let mirrors = vec![
+        serve(
+            "200 OK",
+            "<html><body>This domain is for sale</body></html>",
+        ),
+        serve("200 OK", NYAA_SEARCH),
+    ];
+
+    let fetched = Nyaa
+        .fetch_page(&client(), &mirrors, "tokyo revengers", 1)
+        .unwrap();
+
+    assert_eq!(fetched.page.torrents.len(), 2);
+    assert!(fetched.failovers[0].ends_with("the site's layout may have changed"));
+}
+
+#[test]
+fn reports_status_of_last_mirror() {
+    let mirrors = vec![serve("404 Not Found", "")];
+
+    match Nyaa.fetch_page(&client(), &mirrors, "tokyo revengers", 1) {
+        Err(Error::Status { url, code }) => {
+            assert_eq!(code, 404);
+            assert!(url.starts_with(&mirrors[0]));
+        }
+        _ => panic!("expected a status error"),
+    }
+}
+
+#[test]
+fn reports_challenge_served_with_error_status_as_blocked() {
+    let mirrors = vec![serve("503 Service Unavailable", CLOUDFLARE_CHALLENGE)];
+
+    match Nyaa.fetch_page(&client(), &mirrors, "tokyo revengers", 1) {
+        Err(Error::Blocked(url)) => assert!(url.starts_with(&mirrors[0])),
+        Err(err) => panic!("expected a block page, got {}", err),
+        Ok(_) => panic!("expected a block page"),
+    }
+}
+
+#[test]
+fn errors_when_every_mirror_fails() {
+    let mirrors = vec![dead_mirror(), serve("200 OK", CLOUDFLARE_CHALLENGE)];
+
+    assert!(Nyaa
+        .fetch_page(&client(), &mirrors, "tokyo revengers", 1)
+        .is_err());
+}
+
+#[test]
+fn search_url_uses_mirror() {
+    assert_eq!(
+        Piratebay.search_url("https://tpb.example", "the expanse", 2),
+        "https://tpb.example/search/the%20expanse/2/99/0"
+    );
+}
+
+fn cached_client(name: &str, ttl: Duration) -> (Client, TempDir) {
+    let dir = TempDir::new(name);
+    let cache = Cache::new(dir.path().to_path_buf(), ttl, false);
+    (client().with_cache(Arc::new(cache)), dir)
+}
+
+#[test]
+fn reads_pages_back_from_cache() {
+    let (client, _dir) = cached_client("mirror-cache", Duration::from_secs(60));
+    let mirrors = vec![serve("200 OK", NYAA_SEARCH)];
+
+    let fetched = Nyaa
+        .fetch_page(&client, &mirrors, "tokyo revengers", 1)
+        .unwrap();
+    assert!(!fetched.cached);
+
+    let fetched = Nyaa
+        .fetch_page(&client, &mirrors, "tokyo revengers", 1)
+        .unwrap();
+    assert!(fetched.cached);
+    assert_eq!(fetched.page.torrents.len(), 2);
+    assert_eq!(
+        fetched.page.torrents[0].url,
+        Some(format!("{}/view/1426262", mirrors[0]))
+    );
+}
+
+#[test]
+fn falls_back_to_expired_pages_when_offline() {
+    let (client, _dir) = cached_client("mirror-offline", Duration::from_secs(0));
+    let mirrors = vec![dead_mirror()];
+    let url = Nyaa.search_url(&mirrors[0], "tokyo revengers", 1);
+    client.cache().unwrap().put(&url, 200, NYAA_SEARCH);
+
+    let fetched = Nyaa
+        .fetch_page(&client, &mirrors, "tokyo revengers", 1)
+        .unwrap();
+
+    assert!(fetched.cached);
+    assert_eq!(fetched.page.torrents.len(), 2);
+    assert_eq!(fetched.failovers.len(), 1);
+}
+
+#[test]
+fn configured_mirrors_override_defaults() {
+    let nyaa = provider::find("nyaa").unwrap();
+    let mut configured = HashMap::new();
+
+    assert_eq!(
+        *provider::mirrors(&nyaa, &configured),
+        vec!["https://nyaa.si"]
+    );
+
+    configured.insert(
+        String::from("nyaa"),
+        vec![String::from("https://nyaa.example")],
+    );
+    assert_eq!(
+        *provider::mirrors(&nyaa, &configured),
+        vec!["https://nyaa.example"]
+    );
+}
+
+fn pages_fetched(mirror: String, depth: Option<u32>) -> Vec<Page> {
+    let (tx, rx) = mpsc::channel();
+    let provider: Arc<dyn Provider> = Arc::new(Nyaa);
+
+    provider::query(
+        &Pool::new(1),
+        &provider,
+        &Arc::new(client()),
+        &Arc::new(vec![mirror]),
+        Pages::new(tx, Arc::default()),
+        &Arc::new(String::from("tokyo revengers")),
+        depth,
+    );
+
+    rx.iter().collect()
+}
+
+#[test]
+fn stops_paging_after_a_short_page() {
+    let pages = pages_fetched(serve("200 OK", NYAA_SEARCH), None);
+
+    assert_eq!(pages.len(), 1);
+    assert!(pages[0].last);
+}
+
+#[test]
+fn cancelled_sites_stop_paging() {
+    let (tx, rx) = mpsc::channel();
+    let provider: Arc<dyn Provider> = Arc::new(Nyaa);
+    let cancelled = Arc::new(AtomicBool::new(true));
+
+    provider::query(
+        &Pool::new(1),
+        &provider,
+        &Arc::new(client()),
+        &Arc::new(vec![serve("200 OK", full_nyaa_page())]),
+        Pages::new(tx, cancelled),
+        &Arc::new(String::from("tokyo revengers")),
+        None,
+    );
+
+    assert_eq!(rx.iter().count(), 0);
+}
+
+// a full page that comes back for every page number
+fn full_nyaa_page() -> &'static str {
+    let start = NYAA_SEARCH.find("<tbody>").unwrap() + "<tbody>".len();
+    let end = NYAA_SEARCH.find("</tbody>").unwrap();
+    let full = format!(
+        "{}{}{}",
+        &NYAA_SEARCH[..start],
+        NYAA_SEARCH[start..end].repeat(15),
+        &NYAA_SEARCH[end..]
+    );
+    Box::leak(full.into_boxed_str())
+}
+
+#[test]
+fn stops_paging_when_a_site_repeats_its_last_page() {
+    let full = full_nyaa_page();
+
+    let pages = pages_fetched(serve("200 OK", full), None);
+    let numbers: Vec<u32> = pages.iter().map(|page| page.number).collect();
+    assert_eq!(numbers, vec![1, 2]);
+    assert!(pages[1].last);
+
+    let pages = pages_fetched(serve("200 OK", full), Some(1));
+    assert_eq!(pages.len(), 1);
+    assert!(pages[0].last);
+}
+
+#[test]
+fn stops_paging_at_the_first_error() {
+    let pages = pages_fetched(dead_mirror(), Some(5));
+
+    assert_eq!(pages.len(), 1);
+    assert!(pages[0].result.is_err());
+}
+
+#[test]
+fn deep_searches_take_turns_with_other_sites() {
+    let (tx, rx) = mpsc::channel();
+    let pool = Pool::new(1);
+    let client = Arc::new(client());
+    let query = Arc::new(String::from("the expanse"));
+
+    let nyaa: Arc<dyn Provider> = Arc::new(Nyaa);
+    let piratebay: Arc<dyn Provider> = Arc::new(Piratebay);
+    let nyaa_mirrors = Arc::new(vec![serve("200 OK", full_nyaa_page())]);
+    let piratebay_mirrors = Arc::new(vec![serve("200 OK", PIRATEBAY_SEARCH)]);
+
+    provider::query(
+        &pool,
+        &nyaa,
+        &client,
+        &nyaa_mirrors,
+        Pages::new(tx.clone(), Arc::default()),
+        &query,
+        None,
+    );
+    provider::query(
+        &pool,
+        &piratebay,
+        &client,
+        &piratebay_mirrors,
+        Pages::new(tx, Arc::default()),
+        &query,
+        None,
+    );
+    drop(pool);
+
+    // piratebay's first page goes ahead of nyaa's second with a single worker
+    let order: Vec<(&str, u32)> = rx.iter().map(|page| (page.provider, page.number)).collect();
+    assert_eq!(order, vec![("nyaa", 1), ("piratebay", 1), ("nyaa", 2)]);
+}
@@ -0,0 +1,157 @@
+use chrono::{TimeZone, Utc};
+use serde_json::Value;
+
+use magnetfinder::output::{write_torrents, Template};
+use magnetfinder::release::Release;
+use magnetfinder::size::Size;
+use magnetfinder::types::{OutputFormat, Torrent};
+
+fn torrents() -> Vec<Torrent> {
+    let title = "The.Expanse.S05E10.1080p.WEB.H264-GLHF";
+
+    vec![
+        Torrent {
+            title: String::from(title),
+            magnet: String::from("magnet:?xt=urn:btih:8c3f4b9d1a2e3f4a5b6c7d8e9f0a1b2c3d4e5f60"),
+            size: Size::from_bytes(2630667468),
+            seeders: 1520,
+            leechers: 230,
+            uploaded: Utc.timestamp_opt(1612310400, 0).single(),
+            uploader: Some(String::from("eztv")),
+            category: Some(String::from("Video > TV shows")),
+            sources: vec!["piratebay", "nyaa"],
+            info_hash: Some(String::from("8c3f4b9d1a2e3f4a5b6c7d8e9f0a1b2c3d4e5f60")),
+            url: None,
+            release: Release::parse(title),
+            score: 150,
+            relevance: 100,
+        },
+        Torrent {
+            title: String::from("Title\twith a tab"),
+            magnet: String::from("magnet:?xt=urn:btih:0000000000000000000000000000000000000000"),
+            ..Default::default()
+        },
+    ]
+}
+
+fn write(format: OutputFormat) -> String {
+    let mut out = Vec::new();
+    write_torrents(&mut out, &torrents(), &format).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn parses_output_formats() {
+    assert_eq!("json".parse(), Ok(OutputFormat::Json));
+    assert_eq!("NDJSON".parse(), Ok(OutputFormat::Ndjson));
+    assert_eq!("text".parse(), Ok(OutputFormat::Text));
+    assert_eq!("csv".parse(), Ok(OutputFormat::Csv));
+    assert!("xml".parse::<OutputFormat>().is_err());
+}
+
+#[test]
+fn text_output_keeps_two_columns() {
+    let text = write(OutputFormat::Text);
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert_eq!(lines.len(), 2);
+    assert!(lines.iter().all(|line| line.matches('\t').count() == 1));
+    assert!(lines[1].starts_with("Title with a tab\tmagnet:"));
+}
+
+#[test]
+fn json_output_is_an_array_of_every_field() {
+    let json: Value = serde_json::from_str(&write(OutputFormat::Json)).unwrap();
+    let first = &json[0];
+
+    assert_eq!(json.as_array().unwrap().len(), 2);
+    assert_eq!(first["title"], "The.Expanse.S05E10.1080p.WEB.H264-GLHF");
+    assert_eq!(first["size"], 2630667468u64);
+    assert_eq!(first["seeders"], 1520);
+    assert_eq!(first["leechers"], 230);
+    assert_eq!(first["uploaded"], "2021-02-03T00:00:00Z");
+    assert_eq!(first["uploader"], "eztv");
+    assert_eq!(first["sources"], serde_json::json!(["piratebay", "nyaa"]));
+    assert_eq!(first["url"], Value::Null);
+    assert_eq!(first["score"], 150);
+    assert_eq!(first["relevance"], 100);
+    assert_eq!(first["release"]["resolution"], 1080);
+    assert_eq!(first["release"]["source"], "WEB-DL");
+    assert_eq!(first["release"]["video_codec"], "x264");
+    assert_eq!(first["release"]["episodes"], serde_json::json!([10]));
+}
+
+#[test]
+fn ndjson_output_is_one_object_per_line() {
+    let ndjson = write(OutputFormat::Ndjson);
+    let lines: Vec<&str> = ndjson.lines().collect();
+
+    assert_eq!(lines.len(), 2);
+    for line in lines {
+        let object: Value = serde_json::from_str(line).unwrap();
+        assert!(object.is_object());
+    }
+}
+
+#[test]
+fn empty_json_output_is_an_empty_array() {
+    let mut out = Vec::new();
+    write_torrents(&mut out, &[], &OutputFormat::Json).unwrap();
+
+    assert_eq!(String::from_utf8(out).unwrap(), "[]\n");
+}
+
+fn template(format: &str) -> OutputFormat {
+    OutputFormat::Template(format.parse::<Template>().unwrap())
+}
+
+#[test]
+fn template_fills_in_fields() {
+    let out = write(template(r"{seeders}\t{size}\t{title}\t{magnet}"));
+    let lines: Vec<&str> = out.lines().collect();
+
+    assert_eq!(
+        lines[0],
+        "1520\t2.4 GiB\tThe.Expanse.S05E10.1080p.WEB.H264-GLHF\tmagnet:?xt=urn:btih:8c3f4b9d1a2e3f4a5b6c7d8e9f0a1b2c3d4e5f60"
+    );
+    // values are kept on one line, missing values are empty
+    assert_eq!(lines[1].split('\t').nth(2), Some("Title with a tab"));
+}
+
+#[test]
+fn template_reads_release_and_optional_fields() {
+    let out = write(template(
+        "{{{resolution}}} {group} S{season}E{episodes} {date} [{uploader}]",
+    ));
+    let lines: Vec<&str> = out.lines().collect();
+
+    assert_eq!(lines[0], "{1080p} GLHF S5E10 2021-02-03 [eztv]");
+    assert_eq!(lines[1], "{}  SE  []");
+}
+
+#[test]
+fn template_rejects_invalid_formats() {
+    assert!("{seeders".parse::<Template>().is_err());
+    assert!("{seeds}".parse::<Template>().is_err());
+    assert!("size}".parse::<Template>().is_err());
+    assert!("no fields at all".parse::<Template>().is_ok());
+}
+
+#[test]
+fn csv_output_has_header_and_quoting() {
+    let mut torrents = torrents();
+    torrents[1].title = String::from("Title, with \"quotes\"");
+
+    let mut out = Vec::new();
+    write_torrents(&mut out, &torrents, &OutputFormat::Csv).unwrap();
+    let csv = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("title,magnet,size,size_bytes,seeders,leechers,uploaded,"));
+    assert!(lines[1].starts_with(
+        "The.Expanse.S05E10.1080p.WEB.H264-GLHF,magnet:?xt=urn:btih:8c3f4b9d1a2e3f4a5b6c7d8e9f0a1b2c3d4e5f60,2.4 GiB,2630667468,1520,230,2021-02-03T00:00:00+00:00,"
+    ));
+    assert!(lines[1].contains(",\"piratebay, nyaa\","));
+    assert!(lines[2].starts_with("\"Title, with \"\"quotes\"\"\",magnet:"));
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use magnetfinder::profile::{Profile, Profiles};
+use magnetfinder::release::{Source, VideoCodec};
+use magnetfinder::size::Size;
+use magnetfinder::sort;
+use magnetfinder::types::Media;
+
+use common::torrent;
+
+fn house_profile() -> Profile {
+    Profile {
+        resolutions: vec![1080, 720],
+        codecs: vec![VideoCodec::H265, VideoCodec::H264],
+        groups: vec![String::from("SubsPlease"), String::from("Erai-raws")],
+        forbidden_words: vec![String::from("dual audio")],
+        forbidden_sources: vec![Source::Cam, Source::Telesync],
+        min_size: None,
+        max_size: Some(Size::from_bytes(10 * 1024 * 1024 * 1024)),
+    }
+}
+
+#[test]
+fn scores_preferences_by_rank() {
+    let profile = house_profile();
+
+    assert_eq!(
+        profile.score(&torrent("Movie.2021.1080p.WEB.x265-GRP", "2 GiB", 0)),
+        150
+    );
+    assert_eq!(
+        profile.score(&torrent("Movie.2021.720p.WEB.x264-GRP", "1 GiB", 0)),
+        75
+    );
+    assert_eq!(
+        profile.score(&torrent(
+            "[subsplease] Show - 05 (1080p) [HEVC]",
+            "1 GiB",
+            0
+        )),
+        200
+    );
+    assert_eq!(
+        profile.score(&torrent("Movie.2021.480p.DVDRip", "1 GiB", 0)),
+        0
+    );
+}
+
+#[test]
+fn empty_profile_scores_nothing() {
+    let profile = Profile::default();
+    let t = torrent("Movie.2021.1080p.WEB.x265-GRP", "2 GiB", 0);
+
+    assert_eq!(profile.score(&t), 0);
+    assert!(!profile.is_rejected(&t));
+}
+
+#[test]
+fn rejects_forbidden_results() {
+    let profile = house_profile();
+
+    assert!(profile.is_rejected(&torrent("Movie.2021.HDCAM.x264-GRP", "1 GiB", 0)));
+    assert!(profile.is_rejected(&torrent("Movie.2021.1080p.Dual.Audio.x265", "1 GiB", 0)));
+    assert!(profile.is_rejected(&torrent("Movie.2021.2160p.BluRay.REMUX", "40 GiB", 0)));
+    // forbidden words only match whole words
+    assert!(!profile.is_rejected(&torrent("Cameron.Audio.Dual.1080p", "1 GiB", 0)));
+}
+
+#[test]
+fn apply_drops_rejected_and_sorts_by_score() {
+    let profile = house_profile();
+    let mut torrents = vec![
+        torrent("Movie.2021.720p.WEB.x264-GRP", "1 GiB", 0),
+        torrent("Movie.2021.HDCAM.x264-GRP", "1 GiB", 0),
+        torrent("Movie.2021.1080p.WEB.x265-GRP", "2 GiB", 0),
+        torrent("Movie.2021.1080p.WEB.x264-GRP", "2 GiB", 0),
+    ];
+
+    profile.apply(&mut torrents);
+    sort::sort_torrents(&mut torrents, &sort::parse_sort_keys("score").unwrap());
+
+    let titles: Vec<&str> = torrents.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        vec![
+            "Movie.2021.1080p.WEB.x265-GRP",
+            "Movie.2021.1080p.WEB.x264-GRP",
+            "Movie.2021.720p.WEB.x264-GRP",
+        ]
+    );
+    assert_eq!(torrents[0].score, 150);
+}
+
+#[test]
+fn picks_profile_by_media() {
+    let profiles = Profiles {
+        anime: house_profile(),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        profiles.for_media(Media::Anime).resolutions,
+        vec![1080, 720]
+    );
+    assert!(profiles.for_media(Media::Movie).resolutions.is_empty());
+}
@@ -0,0 +1,213 @@
+mod common;
+
+use magnetfinder::filter::Filter;
+use magnetfinder::release::{self, AudioCodec, Hdr, Release, Source, VideoCodec};
+use magnetfinder::sort;
+
+use common::torrent;
+
+#[test]
+fn parses_scene_episode() {
+    let release = Release::parse("The.Expanse.S05E10.1080p.WEB.H264-GLHF");
+
+    assert_eq!(release.resolution, Some(1080));
+    assert_eq!(release.source, Some(Source::WebDl));
+    assert_eq!(release.video_codec, Some(VideoCodec::H264));
+    assert_eq!(release.group.as_deref(), Some("GLHF"));
+    assert_eq!(release.season, Some(5));
+    assert_eq!(release.episodes, vec![10]);
+    assert!(!release.batch);
+}
+
+#[test]
+fn parses_scene_movie() {
+    let release =
+        Release::parse("Dune.2021.2160p.UHD.BluRay.REMUX.HDR10.DV.HEVC.TrueHD.7.1.Atmos-FGT.mkv");
+
+    assert_eq!(release.resolution, Some(2160));
+    assert_eq!(release.source, Some(Source::BluRay));
+    assert_eq!(release.video_codec, Some(VideoCodec::H265));
+    assert_eq!(release.audio_codec, Some(AudioCodec::TrueHD));
+    assert_eq!(release.hdr, vec![Hdr::Hdr10, Hdr::DolbyVision]);
+    assert_eq!(release.group.as_deref(), Some("FGT"));
+    assert_eq!(release.year, Some(2021));
+    assert_eq!(release.season, None);
+}
+
+#[test]
+fn parses_fansub_episode() {
+    let release = Release::parse("[SubsPlease] Tokyo Revengers - 20 (1080p) [E3D55A3F].mkv");
+
+    assert_eq!(release.group.as_deref(), Some("SubsPlease"));
+    assert_eq!(release.resolution, Some(1080));
+    assert_eq!(release.episodes, vec![20]);
+    assert!(!release.batch);
+
+    let release = Release::parse("[SubsPlease] Kimetsu no Yaiba S2 - 05 (1080p) [ABCD1234].mkv");
+    assert_eq!(release.season, Some(2));
+    assert_eq!(release.episodes, vec![5]);
+    assert!(!release.batch);
+
+    let release = Release::parse("[Judas] Shingeki no Kyojin (Season 2) [1080p][HEVC x265 10bit]");
+    assert_eq!(release.season, Some(2));
+    assert!(release.episodes.is_empty());
+    assert!(release.batch);
+}
+
+#[test]
+fn years_are_not_fansub_episodes() {
+    let release = Release::parse("Anime Movie - 2019 [1080p]");
+
+    assert_eq!(release.year, Some(2019));
+    assert!(release.episodes.is_empty());
+
+    let release = Release::parse("[SubsPlease] One Piece - 1100 (1080p)");
+    assert_eq!(release.episodes, vec![1100]);
+}
+
+#[test]
+fn parses_fansub_batch() {
+    let release =
+        Release::parse("[Erai-raws] Tokyo Revengers - 01 ~ 24 [1080p][Multiple Subtitle][Batch]");
+
+    assert_eq!(release.group.as_deref(), Some("Erai-raws"));
+    assert_eq!(release.episodes, (1..=24).collect::<Vec<u32>>());
+    assert!(release.batch);
+    assert_eq!(release.languages, vec!["Multi Subs"]);
+}
+
+#[test]
+fn parses_season_packs() {
+    let release = Release::parse("The.Expanse.S05.COMPLETE.720p.AMZN.WEBRip.x264-GalaxyTV");
+
+    assert_eq!(release.season, Some(5));
+    assert!(release.episodes.is_empty());
+    assert!(release.batch);
+    assert_eq!(release.source, Some(Source::WebRip));
+    assert_eq!(release.group.as_deref(), Some("GalaxyTV"));
+
+    assert_eq!(release.last_season, None);
+
+    let release = Release::parse("The.Expanse.S01-S05.COMPLETE.1080p.BluRay.x264-GRP");
+    assert_eq!(release.season, Some(1));
+    assert_eq!(release.last_season, Some(5));
+    assert!(release.batch);
+
+    let release = Release::parse("[Judas] Shingeki no Kyojin (Season 2) [1080p][HEVC x265 10bit]");
+    assert_eq!(release.season, Some(2));
+    assert_eq!(release.video_codec, Some(VideoCodec::H265));
+}
+
+#[test]
+fn parses_multi_episode_releases() {
+    let release = Release::parse("Show.Name.S02E01-E03.720p.HDTV.x264-GRP");
+
+    assert_eq!(release.season, Some(2));
+    assert_eq!(release.episodes, vec![1, 2, 3]);
+    assert_eq!(release.source, Some(Source::Hdtv));
+
+    let release = Release::parse("Show Name 3x07 HDTV XviD");
+    assert_eq!(release.season, Some(3));
+    assert_eq!(release.episodes, vec![7]);
+    assert_eq!(release.video_codec, Some(VideoCodec::XviD));
+}
+
+#[test]
+fn parses_yts_titles() {
+    let release = Release::parse("Star Wars: Episode IV - A New Hope (1977) [1080p] [BluRay]");
+
+    assert_eq!(release.resolution, Some(1080));
+    assert_eq!(release.source, Some(Source::BluRay));
+    assert_eq!(release.year, Some(1977));
+    assert_eq!(release.group, None);
+    // "Episode IV - A" is not a fansub episode number
+    assert!(release.episodes.is_empty());
+}
+
+#[test]
+fn parses_cams_and_languages() {
+    let release = Release::parse("New.Movie.2023.HDCAM.Dual.Audio.Hindi.English.x264-XYZ");
+
+    assert_eq!(release.source, Some(Source::Cam));
+    assert_eq!(release.year, Some(2023));
+    assert_eq!(release.languages, vec!["Dual Audio", "English", "Hindi"]);
+}
+
+#[test]
+fn year_at_start_is_part_of_the_name() {
+    let release = Release::parse("1917.2019.1080p.BluRay.x264-SPARKS");
+
+    assert_eq!(release.year, Some(2019));
+    assert_eq!(release.group.as_deref(), Some("SPARKS"));
+}
+
+#[test]
+fn ignores_web_dl_as_group() {
+    let release = Release::parse("Movie Name 2020 720p WEB-DL");
+
+    assert_eq!(release.source, Some(Source::WebDl));
+    assert_eq!(release.group, None);
+}
+
+#[test]
+fn unparsable_titles_are_empty() {
+    assert_eq!(Release::parse("holiday photos"), Release::default());
+}
+
+#[test]
+fn summarizes_quality() {
+    let release = Release::parse("Dune.2021.2160p.WEB-DL.DDP5.1.HDR10+.HEVC-GRP");
+
+    assert_eq!(release.summary(), "2160p WEB-DL x265 HDR10+");
+    assert_eq!(release.audio_codec, Some(AudioCodec::EAC3));
+
+    let release = Release::parse("Movie.2020.1080p.AMZN.WEB-DL.DD+5.1.H.264-GROUP");
+    assert_eq!(release.audio_codec, Some(AudioCodec::EAC3));
+}
+
+#[test]
+fn parses_resolution_and_source_arguments() {
+    assert_eq!(release::parse_resolution_arg("720p"), Ok(720));
+    assert_eq!(release::parse_resolution_arg("1080"), Ok(1080));
+    assert_eq!(release::parse_resolution_arg("4K"), Ok(2160));
+    assert!(release::parse_resolution_arg("hd").is_err());
+
+    assert_eq!(
+        release::parse_sources("cam, ts,web-dl"),
+        Ok(vec![Source::Cam, Source::Telesync, Source::WebDl])
+    );
+    assert!(release::parse_sources("vhs").is_err());
+}
+
+#[test]
+fn filters_on_release_fields() {
+    let filter = Filter {
+        min_resolution: Some(1080),
+        exclude_sources: vec![Source::Cam],
+        ..Default::default()
+    };
+
+    assert!(filter.matches(&torrent("Movie.2021.1080p.WEB.x264-GRP", "0 B", 0)));
+    assert!(!filter.matches(&torrent("Movie.2021.720p.WEB.x264-GRP", "0 B", 0)));
+    assert!(!filter.matches(&torrent("Movie.2021.1080p.HDCAM.x264-GRP", "0 B", 0)));
+    // unknown resolutions are kept
+    assert!(filter.matches(&torrent("Movie 2021", "0 B", 0)));
+}
+
+#[test]
+fn sorts_by_resolution() {
+    let mut torrents = vec![
+        torrent("Movie.720p", "0 B", 0),
+        torrent("Movie", "0 B", 0),
+        torrent("Movie.2160p", "0 B", 0),
+        torrent("Movie.1080p", "0 B", 0),
+    ];
+
+    sort::sort_torrents(&mut torrents, &sort::parse_sort_keys("resolution").unwrap());
+
+    let titles: Vec<&str> = torrents.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        vec!["Movie.2160p", "Movie.1080p", "Movie.720p", "Movie"]
+    );
+}
@@ -0,0 +1,104 @@
+use magnetfinder::filter::Filter;
+use magnetfinder::relevance::{parse_relevance, relevance};
+use magnetfinder::sort;
+use magnetfinder::types::Torrent;
+
+#[test]
+fn exact_phrase_scores_full_marks() {
+    assert_eq!(
+        relevance(
+            "tokyo revengers",
+            "[SubsPlease] Tokyo Revengers - 20 (1080p) [E3D55A3F].mkv"
+        ),
+        100
+    );
+    assert_eq!(
+        relevance("the expanse", "The.Expanse.S05E10.1080p.WEB.H264-GLHF"),
+        100
+    );
+}
+
+#[test]
+fn unrelated_titles_score_nothing() {
+    assert_eq!(
+        relevance("tokyo revengers", "Some.Other.Show.S01E01.720p"),
+        0
+    );
+}
+
+#[test]
+fn partial_matches_score_lower() {
+    let full = relevance("the expanse s05", "The.Expanse.S05E10.1080p");
+    let missing_word = relevance("the expanse season", "The.Expanse.S05E10.1080p");
+    let reordered = relevance("expanse the", "The.Expanse.S05E10.1080p");
+
+    assert!(missing_word < full);
+    assert!(reordered < full);
+    assert!(reordered > missing_word);
+}
+
+#[test]
+fn tolerates_typos_and_partial_words() {
+    let exact = relevance("tokyo revengers", "Tokyo Revengers - 20");
+    let typo = relevance("tokyo revengrs", "Tokyo Revengers - 20");
+    let prefix = relevance("tokyo reven", "Tokyo Revengers - 20");
+
+    assert!(typo > 70 && typo < exact, "typo scored {}", typo);
+    assert!(prefix > 70 && prefix < exact, "prefix scored {}", prefix);
+}
+
+#[test]
+fn numbers_match_exactly() {
+    assert_eq!(relevance("revengers 5", "Tokyo Revengers - 05"), 100);
+    assert!(relevance("revengers 6", "Tokyo Revengers - 05") < 50);
+}
+
+#[test]
+fn empty_query_matches_everything() {
+    assert_eq!(relevance("", "Anything"), 100);
+    assert_eq!(relevance("query", ""), 0);
+}
+
+#[test]
+fn parses_min_relevance() {
+    assert_eq!(parse_relevance("60"), Ok(60));
+    assert_eq!(parse_relevance("75%"), Ok(75));
+    assert!(parse_relevance("101").is_err());
+    assert!(parse_relevance("high").is_err());
+}
+
+#[test]
+fn filters_and_sorts_by_relevance() {
+    let query = "the expanse";
+    let mut torrents: Vec<Torrent> = [
+        "Expanse.Documentary.2019",
+        "The.Expanse.S05E10.1080p",
+        "Unrelated.Show.S01E01",
+    ]
+    .iter()
+    .map(|title| Torrent {
+        title: String::from(*title),
+        relevance: relevance(query, title),
+        ..Default::default()
+    })
+    .collect();
+
+    let filter = Filter {
+        min_relevance: Some(30),
+        ..Default::default()
+    };
+    filter.apply(&mut torrents);
+    sort::sort_torrents(&mut torrents, &sort::parse_sort_keys("relevance").unwrap());
+
+    let titles: Vec<&str> = torrents.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        vec!["The.Expanse.S05E10.1080p", "Expanse.Documentary.2019"]
+    );
+}
+
+#[test]
+fn episode_tags_match_by_prefix() {
+    assert!(relevance("expanse s05", "The.Expanse.S05E10.1080p") > 70);
+    assert!(relevance("movie 202", "Movie.2021.1080p") < 50);
+}
@@ -1,77 +1,288 @@
+use magnetfinder::error::Error;
 use magnetfinder::nyaa;
 use magnetfinder::piratebay;
+use magnetfinder::size::Size;
 use magnetfinder::yts;
 
-use ureq::Agent;
+use chrono::{Duration, Utc};
+
+const NYAA_SEARCH: &str = include_str!("fixtures/nyaa_search.html");
+const NYAA_EMPTY: &str = include_str!("fixtures/nyaa_empty.html");
+const PIRATEBAY_SEARCH: &str = include_str!("fixtures/piratebay_search.html");
+const PIRATEBAY_EMPTY: &str = include_str!("fixtures/piratebay_empty.html");
+const YTS_SEARCH: &str = include_str!("fixtures/yts_search.json");
+const YTS_EMPTY: &str = include_str!("fixtures/yts_empty.json");
 
 #[test]
 fn nyaa_produces_results() {
-    let results = nyaa::fetch_page_results(&Agent::new(), "episode", 1).unwrap();
+    let results = nyaa::parse_page(NYAA_SEARCH).unwrap().torrents;
 
     assert!(!results.is_empty(), "returned torrent vector was empty");
 
-    assert!(
-        results[0].title.to_lowercase().contains("episode"),
-        "returned torrent's title didn't contain search query"
+    assert_eq!(
+        results[0].title,
+        "[SubsPlease] Tokyo Revengers - 20 (1080p) [E3D55A3F].mkv"
     );
     assert!(
-        results[0].magnet.contains("magnet"),
+        results[0]
+            .magnet
+            .starts_with("magnet:?xt=urn:btih:4fbb4ec3bc0c5e1d39ebd7b4ecea1ecc4b7b5a1e"),
         "returned magnet link is incorrect"
     );
-    assert!(
-        !results[0].size.is_empty(),
-        "returned torrent's size was empty"
+    assert_eq!(results[0].size, Size::from_bytes(1503238553));
+    assert_eq!(results[0].seeders, 3177);
+    assert_eq!(results[0].leechers, 139);
+}
+
+#[test]
+fn nyaa_reads_torrent_details() {
+    let results = nyaa::parse_page(NYAA_SEARCH).unwrap().torrents;
+
+    assert_eq!(results[0].sources, vec!["nyaa"]);
+    assert_eq!(
+        results[0].info_hash.as_deref(),
+        Some("4fbb4ec3bc0c5e1d39ebd7b4ecea1ecc4b7b5a1e")
     );
-    assert!(
-        !results[0].seeders.is_empty(),
-        "returned torrent's seeders was empty"
+    assert_eq!(results[0].url.as_deref(), Some("/view/1426262"));
+    assert_eq!(
+        results[0].category.as_deref(),
+        Some("Anime - English-translated")
+    );
+    assert_eq!(
+        results[0].uploaded.map(|date| date.timestamp()),
+        Some(1630162924)
     );
 }
 
+#[test]
+fn nyaa_reads_title_without_comments_link() {
+    let results = nyaa::parse_page(NYAA_SEARCH).unwrap().torrents;
+
+    assert_eq!(
+        results[1].title,
+        "[Erai-raws] Tokyo Revengers - 19 [720p][Multiple Subtitle].mkv"
+    );
+}
+
+#[test]
+fn nyaa_skips_rows_with_missing_cells() {
+    let results = nyaa::parse_page(NYAA_SEARCH).unwrap().torrents;
+
+    assert!(results.iter().all(|t| !t.title.contains("S01E18")));
+    assert!(results.iter().all(|t| !t.title.contains("(480p)")));
+}
+
+#[test]
+fn nyaa_skips_rows_with_malformed_size() {
+    let page = nyaa::parse_page(NYAA_SEARCH).unwrap();
+    let results = page.torrents;
+
+    assert_eq!(results.len(), 2, "row with an unparsable size was kept");
+    assert!(results.iter().all(|t| !t.title.contains("[Anime Time]")));
+    // along with the two rows with missing cells
+    assert_eq!(page.skipped, 3);
+}
+
+#[test]
+fn nyaa_empty_page() {
+    let page = nyaa::parse_page(NYAA_EMPTY).unwrap();
+
+    assert!(page.torrents.is_empty());
+    assert_eq!(page.skipped, 0);
+}
+
 #[test]
 fn piratebay_produces_results() {
-    let results = piratebay::fetch_page_results(&Agent::new(), "episode", 1).unwrap();
+    let results = piratebay::parse_page(PIRATEBAY_SEARCH).unwrap().torrents;
 
     assert!(!results.is_empty(), "returned torrent vector was empty");
 
+    assert_eq!(results[0].title, "The.Expanse.S05E10.1080p.WEB.H264-GLHF");
     assert!(
-        results[0].title.to_lowercase().contains("episode"),
-        "returned torrent's title didn't contain search query"
-    );
-    assert!(
-        results[0].magnet.contains("magnet"),
+        results[0]
+            .magnet
+            .starts_with("magnet:?xt=urn:btih:8c3f4b9d1a2e3f4a5b6c7d8e9f0a1b2c3d4e5f60"),
         "returned magnet link is incorrect"
     );
-    assert!(
-        !results[0].size.is_empty(),
-        "returned torrent's size was empty"
+    assert_eq!(results[0].size, Size::from_bytes(2630667468));
+    assert_eq!(results[0].seeders, 1520);
+    assert_eq!(results[0].leechers, 230);
+}
+
+#[test]
+fn piratebay_reads_torrent_details() {
+    let results = piratebay::parse_page(PIRATEBAY_SEARCH).unwrap().torrents;
+
+    assert_eq!(results[0].sources, vec!["piratebay"]);
+    assert_eq!(
+        results[0].info_hash.as_deref(),
+        Some("8c3f4b9d1a2e3f4a5b6c7d8e9f0a1b2c3d4e5f60")
     );
-    assert!(
-        !results[0].seeders.is_empty(),
-        "returned torrent's seeders was empty"
+    assert_eq!(
+        results[0].url.as_deref(),
+        Some("/torrent/48213376/The.Expanse.S05E10.1080p.WEB.H264-GLHF")
+    );
+    assert_eq!(results[0].category.as_deref(), Some("Video > TV shows"));
+    assert_eq!(results[0].uploader.as_deref(), Some("eztv"));
+    assert_eq!(
+        results[0]
+            .uploaded
+            .map(|date| date.format("%Y-%m-%d").to_string()),
+        Some(String::from("2021-02-03"))
+    );
+}
+
+#[test]
+fn piratebay_reads_relative_upload_dates() {
+    let results = piratebay::parse_page(PIRATEBAY_SEARCH).unwrap().torrents;
+
+    let yesterday = Utc::now() - Duration::days(1);
+    assert_eq!(
+        results[1].uploaded.map(|date| date.date_naive()),
+        Some(yesterday.date_naive())
+    );
+}
+
+#[test]
+fn piratebay_skips_rows_with_missing_cells() {
+    let results = piratebay::parse_page(PIRATEBAY_SEARCH).unwrap().torrents;
+
+    let titles: Vec<&str> = results.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        vec![
+            "The.Expanse.S05E10.1080p.WEB.H264-GLHF",
+            "The.Expanse.S05E09.720p.WEB.x265-MiNX",
+        ]
     );
 }
 
+#[test]
+fn piratebay_empty_page() {
+    assert!(piratebay::parse_page(PIRATEBAY_EMPTY)
+        .unwrap()
+        .torrents
+        .is_empty());
+}
+
 #[test]
 fn yts_produces_results() {
-    let results = yts::fetch_page_results(&Agent::new(), "star", 1).unwrap();
+    let results = yts::parse_page(YTS_SEARCH).unwrap().torrents;
 
-    assert!(!results.is_empty(), "returned torrent vector was empty");
+    assert_eq!(results.len(), 3, "expected one result per movie torrent");
 
-    assert!(
-        results[0].title.to_lowercase().contains("star"),
-        "returned torrent's title didn't contain search query"
+    assert_eq!(
+        results[0].title,
+        "Star Wars: Episode IV - A New Hope (1977) [720p] [BluRay]"
     );
     assert!(
-        results[0].magnet.contains("magnet"),
+        results[0]
+            .magnet
+            .starts_with("magnet:?xt=urn:btih:4f2b6b5c7e9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c"),
         "returned magnet link is incorrect"
     );
+    assert_eq!(results[0].size, Size::from_bytes(1116691497));
+    assert_eq!(results[0].seeders, 412);
+    assert_eq!(results[0].leechers, 37);
+}
+
+#[test]
+fn yts_reads_torrent_details() {
+    let results = yts::parse_page(YTS_SEARCH).unwrap().torrents;
+
+    assert_eq!(results[0].sources, vec!["yts"]);
+    assert_eq!(
+        results[0].info_hash.as_deref(),
+        Some("4f2b6b5c7e9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c")
+    );
+    assert_eq!(
+        results[0].url.as_deref(),
+        Some("https://yts.mx/movies/star-wars-episode-iv-a-new-hope-1977")
+    );
+    assert_eq!(
+        results[0].uploaded.map(|date| date.timestamp()),
+        Some(1490089072)
+    );
+}
+
+#[test]
+fn yts_empty_page() {
+    assert!(yts::parse_page(YTS_EMPTY).unwrap().torrents.is_empty());
+}
+
+#[test]
+fn yts_rejects_malformed_json() {
+    assert!(yts::parse_page("<html>502 Bad Gateway</html>").is_err());
+}
+
+#[test]
+fn fails_when_no_row_can_be_read() {
+    // rows are still there but the title link was renamed
+    let changed = NYAA_SEARCH.replace("td colspan", "td data-colspan");
+
+    assert!(matches!(
+        nyaa::parse_page(&changed),
+        Err(Error::PartialRows {
+            skipped: 5,
+            rows: 5
+        })
+    ));
+}
+
+#[test]
+fn fails_when_no_row_matches() {
+    // the results table is gone, and the page doesn't say nothing was found
+    let start = PIRATEBAY_SEARCH.find("<table").unwrap();
+    let end = PIRATEBAY_SEARCH.find("</table>").unwrap();
+    let changed = format!("{}{}", &PIRATEBAY_SEARCH[..start], &PIRATEBAY_SEARCH[end..]);
+
+    assert!(matches!(
+        piratebay::parse_page(&changed),
+        Err(Error::LayoutChanged { .. })
+    ));
+    assert!(matches!(
+        nyaa::parse_page("<html><body><h3>Browse</h3></body></html>"),
+        Err(Error::LayoutChanged { .. })
+    ));
+}
+
+// repeats the rows between two markers until the page has at least as many as a full page
+fn full_page(body: &str, rows: usize, after: &str, before: &str) -> String {
+    let start = body.find(after).unwrap() + after.len();
+    let end = body.find(before).unwrap();
+    let found = body[start..end].matches("<tr").count();
+
+    format!(
+        "{}{}{}",
+        &body[..start],
+        body[start..end].repeat(rows / found + 1),
+        &body[end..]
+    )
+}
+
+#[test]
+fn short_pages_are_the_last() {
+    assert!(nyaa::parse_page(NYAA_SEARCH).unwrap().last);
+    assert!(nyaa::parse_page(NYAA_EMPTY).unwrap().last);
     assert!(
-        !results[0].size.is_empty(),
-        "returned torrent's size was empty"
+        !nyaa::parse_page(&full_page(NYAA_SEARCH, 75, "<tbody>", "</tbody>"))
+            .unwrap()
+            .last
     );
+
+    assert!(piratebay::parse_page(PIRATEBAY_SEARCH).unwrap().last);
     assert!(
-        !results[0].seeders.is_empty(),
-        "returned torrent's seeders was empty"
+        !piratebay::parse_page(&full_page(PIRATEBAY_SEARCH, 30, "</thead>", "</table>"))
+            .unwrap()
+            .last
     );
 }
+
+#[test]
+fn yts_stops_at_the_reported_movie_count() {
+    assert!(yts::parse_page(YTS_SEARCH).unwrap().last);
+    assert!(yts::parse_page(YTS_EMPTY).unwrap().last);
+
+    let more = YTS_SEARCH.replace("\"movie_count\": 2", "\"movie_count\": 45");
+    assert!(!yts::parse_page(&more).unwrap().last);
+}
@@ -0,0 +1,169 @@
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use magnetfinder::error::Error;
+use magnetfinder::provider::{Fetched, ParsedPage};
+use magnetfinder::search::{parse_depth, Page, Search};
+use magnetfinder::types::Torrent;
+
+fn page(provider: &'static str, titles: &[&str]) -> Page {
+    let torrents = titles
+        .iter()
+        .map(|title| Torrent {
+            title: String::from(*title),
+            ..Default::default()
+        })
+        .collect();
+
+    Page {
+        provider,
+        number: 1,
+        result: Ok(Fetched {
+            page: ParsedPage {
+                torrents,
+                skipped: 0,
+                last: true,
+            },
+            mirror: format!("https://{}.example", provider),
+            status: 200,
+            failovers: Vec::new(),
+            cached: false,
+        }),
+        latency: Duration::from_millis(100),
+        last: true,
+    }
+}
+
+// a page with more pages after it
+fn more(mut page: Page) -> Page {
+    page.last = false;
+    page
+}
+
+fn titles(search: &Search) -> Vec<&str> {
+    search.torrents().iter().map(|t| t.title.as_str()).collect()
+}
+
+#[test]
+fn collects_pages_as_they_arrive() {
+    let (tx, rx) = mpsc::channel();
+    let mut search = Search::new(rx, &[("nyaa", 1), ("yts", 1)], None);
+
+    assert_eq!(search.loading(), vec!["nyaa", "yts"]);
+    assert!(!search.poll(Duration::from_millis(10)));
+
+    tx.send(page("yts", &["Dune"])).unwrap();
+    assert!(search.poll(Duration::from_millis(10)));
+    assert_eq!(titles(&search), vec!["Dune"]);
+    assert_eq!(search.loading(), vec!["nyaa"]);
+
+    tx.send(more(page("nyaa", &["Frieren - 01"]))).unwrap();
+    search.poll(Duration::from_millis(10));
+    assert_eq!(search.loading(), vec!["nyaa"]);
+
+    tx.send(page("nyaa", &["Frieren - 02"])).unwrap();
+    search.wait();
+
+    assert!(search.is_finished());
+    assert!(search.timed_out().is_empty());
+    assert_eq!(
+        titles(&search),
+        vec!["Dune", "Frieren - 01", "Frieren - 02"]
+    );
+}
+
+#[test]
+fn leaves_out_sites_that_time_out() {
+    let (tx, rx) = mpsc::channel();
+    let mut search = Search::new(
+        rx,
+        &[("nyaa", 1), ("piratebay", 1)],
+        Some(Duration::from_millis(50)),
+    );
+
+    tx.send(page("nyaa", &["Frieren - 01"])).unwrap();
+    search.wait();
+
+    assert!(search.is_finished());
+    assert_eq!(search.timed_out(), vec!["piratebay"]);
+    // the site's remaining pages aren't fetched
+    assert!(search.cancelled("piratebay").load(Ordering::Relaxed));
+    assert!(!search.cancelled("nyaa").load(Ordering::Relaxed));
+    assert!(search.loading().is_empty());
+
+    // late pages are ignored
+    tx.send(page("piratebay", &["Late"])).unwrap();
+    assert!(!search.poll(Duration::from_millis(10)));
+    assert_eq!(titles(&search), vec!["Frieren - 01"]);
+}
+
+#[test]
+fn finishes_when_every_thread_is_done() {
+    let (tx, rx) = mpsc::channel();
+    let mut search = Search::new(rx, &[("yts", 3)], None);
+
+    tx.send(more(page("yts", &["Dune"]))).unwrap();
+    drop(tx);
+    search.wait();
+
+    assert!(search.is_finished());
+    assert_eq!(search.into_torrents().len(), 1);
+}
+
+#[test]
+fn summarizes_each_provider() {
+    let (tx, rx) = mpsc::channel();
+    let mut search = Search::new(rx, &[("nyaa", 1), ("yts", 1)], None);
+
+    tx.send(more(page("nyaa", &["Frieren - 01", "Frieren - 02"])))
+        .unwrap();
+    tx.send(Page {
+        provider: "nyaa",
+        number: 2,
+        result: Err(Error::NoMirrors("nyaa")),
+        latency: Duration::from_millis(300),
+        last: true,
+    })
+    .unwrap();
+
+    let mut layout_changed = page("yts", &[]);
+    if let Ok(fetched) = &mut layout_changed.result {
+        fetched.page.skipped = 20;
+    }
+    tx.send(layout_changed).unwrap();
+    search.wait();
+
+    let stats = search.stats();
+    assert_eq!(stats[0].pages, 1);
+    assert_eq!(stats[0].failed_pages, 1);
+    assert_eq!(stats[0].results, 2);
+    assert_eq!(stats[0].mirrors, vec!["https://nyaa.example"]);
+
+    let summary = search.summary(false);
+    assert_eq!(
+        summary[0],
+        "nyaa: 1 page, 2 results, HTTP 200, 200 ms per page, from https://nyaa.example, \
+         1 page failed: no mirrors configured for nyaa"
+    );
+    assert!(summary[1].starts_with(
+        "yts: 1 page, 0 results, 20 of 20 rows couldn't be read, the site's layout may have changed"
+    ));
+
+    // verbose adds a line per page
+    let verbose = search.summary(true);
+    assert_eq!(verbose.len(), 5);
+    assert_eq!(
+        verbose[2],
+        "nyaa page 1: 2 results, 0 rows skipped, HTTP 200 from https://nyaa.example in 100 ms"
+    );
+}
+
+#[test]
+fn parses_depth() {
+    assert_eq!(parse_depth("3"), Ok(Some(3)));
+    assert_eq!(parse_depth(" ALL "), Ok(None));
+    assert!(parse_depth("0").is_err());
+    assert!(parse_depth("-1").is_err());
+    assert!(parse_depth("many").is_err());
+}
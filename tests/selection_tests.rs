@@ -0,0 +1,63 @@
+use magnetfinder::selection::{parse_command, parse_selection, Command};
+use magnetfinder::types::{Order, Sort, SortKey};
+
+#[test]
+fn selects_numbers_ranges_and_lists() {
+    assert_eq!(parse_selection("2", 10), Ok(vec![1]));
+    assert_eq!(parse_selection("1 3", 10), Ok(vec![0, 2]));
+    assert_eq!(parse_selection("1,2", 10), Ok(vec![0, 1]));
+    assert_eq!(parse_selection("  3-5 ,  8   ", 10), Ok(vec![2, 3, 4, 7]));
+    // overlapping picks are only selected once
+    assert_eq!(parse_selection("4-6 5 6-7", 10), Ok(vec![3, 4, 5, 6]));
+}
+
+#[test]
+fn selects_all_and_inversions() {
+    assert_eq!(parse_selection("all", 4), Ok(vec![0, 1, 2, 3]));
+    assert_eq!(parse_selection("ALL", 2), Ok(vec![0, 1]));
+    assert_eq!(parse_selection("^2", 4), Ok(vec![0, 2, 3]));
+    assert_eq!(parse_selection("^2-3 ^1", 5), Ok(vec![3, 4]));
+    assert_eq!(parse_selection("1-4 ^3", 10), Ok(vec![0, 1, 3]));
+}
+
+#[test]
+fn rejects_invalid_selections() {
+    assert!(parse_selection("0", 5).is_err());
+    assert!(parse_selection("6", 5).is_err());
+    assert!(parse_selection("2-9", 5).is_err());
+    assert!(parse_selection("4-2", 5).is_err());
+    assert!(parse_selection("one", 5).is_err());
+    assert!(parse_selection("^1-2", 2).is_err());
+    assert!(parse_selection("1", 0).is_err());
+}
+
+#[test]
+fn parses_prompt_commands() {
+    assert_eq!(parse_command("n", 5), Ok(Command::Next));
+    assert_eq!(parse_command(" P \n", 5), Ok(Command::Previous));
+    assert_eq!(parse_command("q", 5), Ok(Command::Quit));
+    assert_eq!(
+        parse_command("s size", 5),
+        Ok(Command::Sort(vec![SortKey {
+            sort: Sort::Size,
+            order: Order::Descending
+        }]))
+    );
+    assert_eq!(
+        parse_command("f  1080p x265", 5),
+        Ok(Command::Filter(String::from("1080p x265")))
+    );
+    assert_eq!(parse_command("f", 5), Ok(Command::Filter(String::new())));
+    assert_eq!(parse_command("i 3", 5), Ok(Command::Inspect(2)));
+    assert_eq!(parse_command("1-2", 5), Ok(Command::Select(vec![0, 1])));
+}
+
+#[test]
+fn rejects_invalid_commands() {
+    assert!(parse_command("", 5).is_err());
+    assert!(parse_command("s", 5).is_err());
+    assert!(parse_command("s bogus", 5).is_err());
+    assert!(parse_command("i 1-2", 5).is_err());
+    assert!(parse_command("i 9", 5).is_err());
+    assert!(parse_command("x", 5).is_err());
+}
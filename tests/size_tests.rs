@@ -0,0 +1,90 @@
+use magnetfinder::size::{ParseSizeError, Size};
+
+#[test]
+fn parses_binary_units() {
+    assert_eq!("1.4 GiB".parse(), Ok(Size::from_bytes(1503238553)));
+    assert_eq!("702.3 MiB".parse(), Ok(Size::from_bytes(736414924)));
+    assert_eq!("1 TiB".parse(), Ok(Size::from_bytes(1 << 40)));
+    assert_eq!("12 KiB".parse(), Ok(Size::from_bytes(12288)));
+}
+
+#[test]
+fn keeps_decimal_units_apart() {
+    assert_eq!("1.04 GB".parse(), Ok(Size::from_bytes(1_040_000_000)));
+    assert_eq!("700MB".parse(), Ok(Size::from_bytes(700_000_000)));
+    assert_ne!("1 GB".parse::<Size>(), "1 GiB".parse::<Size>());
+}
+
+#[test]
+fn parses_piratebay_forms() {
+    assert_eq!("2.45&nbsp;GiB".parse(), Ok(Size::from_bytes(2630667468)));
+    assert_eq!("201.7\u{a0}MiB".parse(), Ok(Size::from_bytes(211497779)));
+}
+
+#[test]
+fn parses_bytes() {
+    assert_eq!("512 Bytes".parse(), Ok(Size::from_bytes(512)));
+    assert_eq!("512 B".parse(), Ok(Size::from_bytes(512)));
+    assert_eq!("1,024".parse(), Ok(Size::from_bytes(1024)));
+    assert_eq!("1,234,567.5 KB".parse(), Ok(Size::from_bytes(1234567500)));
+}
+
+#[test]
+fn reports_parse_failures() {
+    assert_eq!("".parse::<Size>(), Err(ParseSizeError::Empty));
+    assert_eq!(
+        "5.9O GB".parse::<Size>(),
+        Err(ParseSizeError::UnknownUnit(String::from("O GB")))
+    );
+    assert_eq!(
+        "about 1 gig".parse::<Size>(),
+        Err(ParseSizeError::UnknownUnit(String::from("about 1 gig")))
+    );
+    assert_eq!(
+        "1.2.3 GiB".parse::<Size>(),
+        Err(ParseSizeError::InvalidNumber(String::from("1.2.3")))
+    );
+    assert_eq!(
+        "1,5 GB".parse::<Size>(),
+        Err(ParseSizeError::InvalidNumber(String::from("1,5")))
+    );
+    assert_eq!(
+        "1,0000".parse::<Size>(),
+        Err(ParseSizeError::InvalidNumber(String::from("1,0000")))
+    );
+    assert_eq!(
+        ",500 MB".parse::<Size>(),
+        Err(ParseSizeError::InvalidNumber(String::from(",500")))
+    );
+    assert_eq!(
+        "0.5".parse::<Size>(),
+        Err(ParseSizeError::FractionalBytes(String::from("0.5")))
+    );
+    assert_eq!(
+        "5.".parse::<Size>(),
+        Err(ParseSizeError::FractionalBytes(String::from("5.")))
+    );
+    assert_eq!(
+        "1.5 Bytes".parse::<Size>(),
+        Err(ParseSizeError::FractionalBytes(String::from("1.5")))
+    );
+    assert_eq!(
+        "99999999 PiB".parse::<Size>(),
+        Err(ParseSizeError::TooLarge)
+    );
+}
+
+#[test]
+fn orders_by_bytes() {
+    let small: Size = "900 MiB".parse().unwrap();
+    let large: Size = "1 GB".parse().unwrap();
+
+    assert!(small < large);
+}
+
+#[test]
+fn displays_human_readable() {
+    assert_eq!(Size::from_bytes(1503238553).to_string(), "1.4 GiB");
+    assert_eq!(Size::from_bytes(736414924).to_string(), "702.3 MiB");
+    assert_eq!(Size::from_bytes(512).to_string(), "512 B");
+}
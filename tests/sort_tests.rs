@@ -0,0 +1,99 @@
+mod common;
+
+use magnetfinder::sort;
+use magnetfinder::types::{Order, Sort, SortKey, Torrent};
+
+use common::{titles, torrent};
+
+#[test]
+fn parses_keys_with_orders() {
+    assert_eq!(
+        sort::parse_sort_keys("seeds:desc,size:asc").unwrap(),
+        vec![
+            SortKey {
+                sort: Sort::Seeds,
+                order: Order::Descending
+            },
+            SortKey {
+                sort: Sort::Size,
+                order: Order::Ascending
+            },
+        ]
+    );
+}
+
+#[test]
+fn uses_default_order_per_key() {
+    let keys = sort::parse_sort_keys("size,title").unwrap();
+
+    assert_eq!(keys[0].order, Order::Descending);
+    assert_eq!(keys[1].order, Order::Ascending);
+}
+
+#[test]
+fn rejects_unknown_keys() {
+    assert!(sort::parse_sort_keys("popularity").is_err());
+    assert!(sort::parse_sort_keys("seeds:sideways").is_err());
+    assert!(sort::parse_sort_keys("").is_err());
+}
+
+#[test]
+fn sorts_by_multiple_keys() {
+    let mut torrents = vec![
+        torrent("b", "300 B", 10),
+        torrent("a", "100 B", 50),
+        torrent("c", "200 B", 10),
+    ];
+
+    sort::sort_torrents(
+        &mut torrents,
+        &sort::parse_sort_keys("seeds:desc,size:asc").unwrap(),
+    );
+
+    assert_eq!(titles(&torrents), vec!["a", "c", "b"]);
+}
+
+#[test]
+fn sorts_ascending() {
+    let mut torrents = vec![
+        torrent("b", "300 B", 1),
+        torrent("a", "100 B", 1),
+        torrent("c", "200 B", 1),
+    ];
+
+    sort::sort_torrents(&mut torrents, &sort::parse_sort_keys("size:asc").unwrap());
+
+    assert_eq!(titles(&torrents), vec!["a", "c", "b"]);
+}
+
+#[test]
+fn sorts_by_ratio() {
+    let mut torrents = vec![
+        Torrent {
+            leechers: 10,
+            ..torrent("even", "0 B", 10)
+        },
+        torrent("no leechers", "0 B", 5),
+        Torrent {
+            leechers: 10,
+            ..torrent("healthy", "0 B", 90)
+        },
+    ];
+
+    sort::sort_torrents(&mut torrents, &sort::parse_sort_keys("ratio").unwrap());
+
+    assert_eq!(titles(&torrents), vec!["healthy", "no leechers", "even"]);
+}
+
+#[test]
+fn sorts_titles_case_insensitively() {
+    let mut torrents = vec![
+        torrent("beta", "0 B", 0),
+        torrent("Alpha", "0 B", 0),
+        torrent("gamma", "0 B", 0),
+    ];
+
+    sort::sort_torrents(&mut torrents, &sort::parse_sort_keys("title").unwrap());
+
+    assert_eq!(titles(&torrents), vec!["Alpha", "beta", "gamma"]);
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+use magnetfinder::trackers::{self, TrackerList, TrackerMode, Trackers};
+
+const MAGNET: &str = "magnet:?xt=urn:btih:c12fe1c06bba254a9dc9f519b335aa7c1367a88a&dn=release&tr=udp%3A%2F%2Fsite.example%3A80";
+
+fn list(mode: TrackerMode, trackers: &[&str]) -> TrackerList {
+    TrackerList {
+        mode,
+        trackers: trackers.iter().map(|t| String::from(*t)).collect(),
+    }
+}
+
+#[test]
+fn extends_site_trackers() {
+    let trackers = list(TrackerMode::Extend, &["udp://extra.example:1337"]);
+
+    assert_eq!(
+        trackers.apply(MAGNET),
+        format!("{}&tr=udp%3A%2F%2Fextra.example%3A1337", MAGNET)
+    );
+}
+
+#[test]
+fn replaces_site_trackers() {
+    let trackers = list(TrackerMode::Replace, &["udp://extra.example:1337"]);
+
+    assert_eq!(
+        trackers.apply(MAGNET),
+        "magnet:?xt=urn:btih:c12fe1c06bba254a9dc9f519b335aa7c1367a88a&dn=release&tr=udp%3A%2F%2Fextra.example%3A1337"
+    );
+}
+
+#[test]
+fn leaves_magnet_alone_without_trackers() {
+    assert_eq!(TrackerList::default().apply(MAGNET), MAGNET);
+}
+
+#[test]
+fn provider_list_overrides_global() {
+    let mut providers = HashMap::new();
+    providers.insert(
+        String::from("yts"),
+        list(TrackerMode::Replace, &["udp://yts.example:80"]),
+    );
+    let trackers = Trackers {
+        global: list(TrackerMode::Extend, &["udp://global.example:80"]),
+        providers,
+    };
+
+    assert_eq!(
+        trackers.for_provider("yts").trackers,
+        vec!["udp://yts.example:80"]
+    );
+    assert_eq!(
+        trackers.for_provider("nyaa").trackers,
+        vec!["udp://global.example:80"]
+    );
+}
+
+#[test]
+fn reads_trackerslist_file() {
+    let path = env::temp_dir().join("magnetfinder_trackers_best.txt");
+    fs::write(
+        &path,
+        "udp://tracker.opentrackr.org:1337/announce\n\n# comment\nudp://open.stealth.si:80/announce\n\n",
+    )
+    .unwrap();
+
+    let trackers = trackers::read_tracker_file(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        trackers,
+        vec![
+            "udp://tracker.opentrackr.org:1337/announce",
+            "udp://open.stealth.si:80/announce",
+        ]
+    );
+}
+
+#[test]
+fn rejects_unknown_mode() {
+    assert!(TrackerMode::new("append").is_err());
+}
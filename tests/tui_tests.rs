@@ -0,0 +1,161 @@
+#![cfg(feature = "tui")]
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use magnetfinder::tui::{Action, Browser};
+use magnetfinder::types::Torrent;
+
+fn torrents() -> Vec<Torrent> {
+    [
+        ("The.Expanse.S05E10.1080p", 100, 3),
+        ("The.Expanse.S05E09.720p", 300, 1),
+        ("Tokyo Revengers - 20", 200, 2),
+    ]
+    .iter()
+    .map(|(title, seeders, gib)| Torrent {
+        title: String::from(*title),
+        seeders: *seeders,
+        size: magnetfinder::size::Size::from_bytes(gib << 30),
+        ..Default::default()
+    })
+    .collect()
+}
+
+fn press(browser: &mut Browser, keys: &str) -> Action {
+    let mut action = Action::Continue;
+    for c in keys.chars() {
+        action = browser.handle_key(KeyEvent::from(KeyCode::Char(c)));
+    }
+    action
+}
+
+fn key(browser: &mut Browser, code: KeyCode) -> Action {
+    browser.handle_key(KeyEvent::from(code))
+}
+
+#[test]
+fn starts_in_search_order() {
+    let torrents = torrents();
+    let browser = Browser::new(&torrents);
+
+    assert_eq!(browser.visible(), &[0, 1, 2]);
+    assert_eq!(browser.current(), Some(0));
+    assert_eq!(browser.selection(), vec![0]);
+}
+
+#[test]
+fn moves_and_multi_selects() {
+    let torrents = torrents();
+    let mut browser = Browser::new(&torrents);
+
+    // space marks the row and moves down
+    press(&mut browser, " ");
+    assert_eq!(browser.current(), Some(1));
+    key(&mut browser, KeyCode::Down);
+    press(&mut browser, " ");
+
+    assert_eq!(browser.selection(), vec![0, 2]);
+    assert_eq!(key(&mut browser, KeyCode::Enter), Action::Confirm);
+
+    // marking a marked row unmarks it
+    key(&mut browser, KeyCode::Home);
+    press(&mut browser, " ");
+    assert_eq!(browser.selection(), vec![2]);
+
+    press(&mut browser, "G");
+    assert_eq!(browser.current(), Some(2));
+    key(&mut browser, KeyCode::Down);
+    assert_eq!(browser.current(), Some(2));
+}
+
+#[test]
+fn filters_titles_live() {
+    let torrents = torrents();
+    let mut browser = Browser::new(&torrents);
+
+    press(&mut browser, "/expanse 720");
+    assert_eq!(browser.visible(), &[1]);
+
+    // while filtering 'q' is part of the filter, not quit
+    assert_eq!(press(&mut browser, "q"), Action::Continue);
+    assert!(browser.visible().is_empty());
+    assert_eq!(browser.current(), None);
+
+    key(&mut browser, KeyCode::Backspace);
+    key(&mut browser, KeyCode::Enter);
+    assert_eq!(browser.visible(), &[1]);
+
+    // esc clears the filter before quitting
+    assert_eq!(key(&mut browser, KeyCode::Esc), Action::Continue);
+    assert_eq!(browser.visible(), &[0, 1, 2]);
+    assert_eq!(key(&mut browser, KeyCode::Esc), Action::Quit);
+}
+
+#[test]
+fn cycles_sort_keys_and_reverses() {
+    let torrents = torrents();
+    let mut browser = Browser::new(&torrents);
+
+    // seeds, largest first
+    press(&mut browser, "s");
+    assert_eq!(browser.visible(), &[1, 2, 0]);
+
+    press(&mut browser, "r");
+    assert_eq!(browser.visible(), &[0, 2, 1]);
+
+    // score and relevance are all equal here, then size
+    press(&mut browser, "sss");
+    assert_eq!(browser.visible(), &[0, 2, 1]);
+}
+
+#[test]
+fn mark_all_toggles_visible_rows() {
+    let torrents = torrents();
+    let mut browser = Browser::new(&torrents);
+
+    press(&mut browser, "/expanse");
+    key(&mut browser, KeyCode::Enter);
+    press(&mut browser, "a");
+    assert_eq!(browser.selection(), vec![0, 1]);
+
+    press(&mut browser, "a");
+    assert_eq!(browser.selection(), vec![0]);
+}
+
+#[test]
+fn ctrl_c_quits() {
+    let torrents = torrents();
+    let mut browser = Browser::new(&torrents);
+
+    let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+    assert_eq!(browser.handle_key(ctrl_c), Action::Quit);
+}
+
+#[test]
+fn renders_table_details_and_status() {
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    let torrents = torrents();
+    let mut browser = Browser::new(&torrents);
+    press(&mut browser, " ");
+
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|frame| browser.draw(frame)).unwrap();
+
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("[x]"));
+    assert!(screen.contains("Tokyo Revengers - 20"));
+    assert!(screen.contains("Details"));
+    assert!(screen.contains("1 marked"));
+
+    // tiny terminals don't panic
+    let mut terminal = Terminal::new(TestBackend::new(20, 5)).unwrap();
+    terminal.draw(|frame| browser.draw(frame)).unwrap();
+}